@@ -43,6 +43,8 @@ fn create_gnosis_post_merge_header() -> GnosisHeader {
         excess_blob_gas: Some(2_621_440),
         parent_beacon_block_root: Some(B256::random()),
         requests_hash: Some(B256::random()),
+        block_access_list_hash: None,
+        trailing_fields: Vec::new(),
     }
 }
 
@@ -71,6 +73,8 @@ fn create_gnosis_pre_merge_header() -> GnosisHeader {
         excess_blob_gas: None,
         parent_beacon_block_root: None,
         requests_hash: None,
+        block_access_list_hash: None,
+        trailing_fields: Vec::new(),
     }
 }
 
@@ -0,0 +1,295 @@
+use alloy_primitives::{Address, FixedBytes, Signature, U256};
+pub use k256::ecdsa::SigningKey;
+
+use crate::header::GnosisHeader;
+
+/// Errors returned when checking the AuRa seal of a header.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum SealError {
+    /// The header has no AuRa seal, it is not a pre-merge header.
+    #[display("header is not sealed by AuRa")]
+    NotAuRa,
+    /// The seal is not a valid recoverable signature.
+    #[display("invalid AuRa seal signature")]
+    InvalidSignature,
+    /// The validator set is empty, so no validator can seal blocks.
+    #[display("empty validator set")]
+    EmptyValidatorSet,
+    /// The seal was not produced by the validator whose turn it is.
+    #[display("step {step} belongs to {expected}, seal signed by {signer}")]
+    WrongProposer {
+        /// The AuRa step of the header.
+        step: U256,
+        /// The validator expected to seal the step.
+        expected: Address,
+        /// The recovered signer.
+        signer: Address,
+    },
+    /// The header to seal already carries post-merge fields.
+    #[display("cannot seal a post-merge header")]
+    PostMerge,
+    /// Signing the seal hash failed.
+    #[display("failed to sign the AuRa seal")]
+    Signing,
+    /// The beneficiary of the header is not the signer of the seal.
+    #[display("beneficiary {beneficiary} did not sign the seal, {signer} did")]
+    BeneficiaryMismatch {
+        /// The beneficiary of the header.
+        beneficiary: Address,
+        /// The recovered signer.
+        signer: Address,
+    },
+}
+
+/// Recovers the address that signed the AuRa seal of a pre-merge header.
+///
+/// The seal is a 65 bytes `r || s || v` signature over [`GnosisHeader::aura_seal_hash`], with `v`
+/// being the recovery id.
+pub fn recover_seal_signer(header: &GnosisHeader) -> Result<Address, SealError> {
+    if !header.is_pre_merge() {
+        return Err(SealError::NotAuRa);
+    }
+    let seal = header.aura_seal.as_ref().ok_or(SealError::NotAuRa)?;
+    let signature = Signature::from_raw_array(seal).map_err(|_| SealError::InvalidSignature)?;
+    signature
+        .recover_address_from_prehash(&header.aura_seal_hash())
+        .map_err(|_| SealError::InvalidSignature)
+}
+
+/// Returns the validator whose turn it is to seal the given step.
+///
+/// Validators take turns in order, so the proposer is `validators[step % validators.len()]`.
+/// Returns `None` if the validator set is empty.
+pub fn step_proposer(step: U256, validators: &[Address]) -> Option<Address> {
+    if validators.is_empty() {
+        return None;
+    }
+    let index = step % U256::from(validators.len());
+    validators.get(index.to::<usize>()).copied()
+}
+
+/// Returns the difficulty of an AuRa block sealed at `step` on top of a block sealed at
+/// `parent_step`.
+///
+/// This is the score of OpenEthereum and Nethermind without empty steps,
+/// `u128::MAX + parent_step - step`. A block sealed at the step right after its parent has a
+/// difficulty of `u128::MAX - 1`.
+pub fn block_difficulty(parent_step: U256, step: U256) -> U256 {
+    (U256::from(u128::MAX) + parent_step).saturating_sub(step)
+}
+
+/// Checks that a pre-merge header was sealed by the expected validator of its step.
+///
+/// Returns the signer on success.
+pub fn verify_seal(header: &GnosisHeader, validators: &[Address]) -> Result<Address, SealError> {
+    let signer = recover_seal_signer(header)?;
+    let step = header.aura_step.ok_or(SealError::NotAuRa)?;
+    let expected = step_proposer(step, validators).ok_or(SealError::EmptyValidatorSet)?;
+    if signer != expected {
+        return Err(SealError::WrongProposer {
+            step,
+            expected,
+            signer,
+        });
+    }
+    if header.beneficiary != signer {
+        return Err(SealError::BeneficiaryMismatch {
+            beneficiary: header.beneficiary,
+            signer,
+        });
+    }
+    Ok(signer)
+}
+
+/// Seals a pre-merge header for `step` with a local validator key.
+///
+/// The beneficiary is set to the key's address, as AuRa requires. Like the rest of the seal, the
+/// step is not covered by the signature. The returned header passes [`verify_seal`] for any
+/// validator set in which the key's address owns `step`.
+pub fn seal_header(
+    mut header: GnosisHeader,
+    step: U256,
+    key: &SigningKey,
+) -> Result<GnosisHeader, SealError> {
+    if header.mix_hash.is_some() || header.nonce.is_some() {
+        return Err(SealError::PostMerge);
+    }
+    header.beneficiary = Address::from_private_key(key);
+    header.aura_step = Some(step);
+    header.aura_seal = Some(FixedBytes::ZERO);
+
+    let (signature, recovery_id) = key
+        .sign_prehash_recoverable(header.aura_seal_hash().as_slice())
+        .map_err(|_| SealError::Signing)?;
+    let mut seal = [0u8; 65];
+    seal[..64].copy_from_slice(&signature.to_bytes());
+    seal[64] = recovery_id.to_byte();
+    header.aura_seal = Some(seal.into());
+    Ok(header)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{B64, B256};
+
+    fn key(byte: u8) -> SigningKey {
+        SigningKey::from_slice(&[byte; 32]).unwrap()
+    }
+
+    fn sealed_header(step: u64, key: &SigningKey) -> GnosisHeader {
+        let header = GnosisHeader {
+            number: 42,
+            gas_limit: 17_000_000,
+            timestamp: step * 5,
+            ..Default::default()
+        };
+        seal_header(header, U256::from(step), key).unwrap()
+    }
+
+    #[test]
+    fn test_recover_seal_signer() {
+        let key = key(1);
+        let header = sealed_header(7, &key);
+        assert_eq!(
+            recover_seal_signer(&header),
+            Ok(Address::from_private_key(&key))
+        );
+
+        let mut tampered = header.clone();
+        tampered.gas_used = 1;
+        assert_ne!(
+            recover_seal_signer(&tampered),
+            Ok(Address::from_private_key(&key))
+        );
+
+        let mut invalid = header;
+        invalid.aura_seal = Some(FixedBytes::repeat_byte(0xff));
+        assert_eq!(
+            recover_seal_signer(&invalid),
+            Err(SealError::InvalidSignature)
+        );
+    }
+
+    #[test]
+    fn test_recover_seal_signer_post_merge() {
+        let header = GnosisHeader {
+            mix_hash: Some(B256::ZERO),
+            nonce: Some(B64::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(recover_seal_signer(&header), Err(SealError::NotAuRa));
+    }
+
+    #[test]
+    fn test_step_proposer() {
+        let validators = [Address::with_last_byte(1), Address::with_last_byte(2)];
+        assert_eq!(
+            step_proposer(U256::from(4), &validators),
+            Some(validators[0])
+        );
+        assert_eq!(
+            step_proposer(U256::from(5), &validators),
+            Some(validators[1])
+        );
+        assert_eq!(step_proposer(U256::MAX, &validators), Some(validators[1]));
+        assert_eq!(step_proposer(U256::from(5), &[]), None);
+    }
+
+    #[test]
+    fn test_block_difficulty() {
+        assert_eq!(
+            block_difficulty(U256::from(10), U256::from(11)),
+            U256::from(u128::MAX - 1)
+        );
+        // Skipped steps lower the difficulty
+        assert_eq!(
+            block_difficulty(U256::from(10), U256::from(13)),
+            U256::from(u128::MAX - 3)
+        );
+    }
+
+    #[test]
+    fn test_verify_seal() {
+        let (first, second) = (key(1), key(2));
+        let validators = [
+            Address::from_private_key(&first),
+            Address::from_private_key(&second),
+        ];
+
+        let header = sealed_header(10, &first);
+        assert_eq!(verify_seal(&header, &validators), Ok(validators[0]));
+        assert_eq!(verify_seal(&header, &[]), Err(SealError::EmptyValidatorSet));
+
+        // Odd steps belong to the second validator
+        let header = sealed_header(11, &first);
+        assert_eq!(
+            verify_seal(&header, &validators),
+            Err(SealError::WrongProposer {
+                step: U256::from(11),
+                expected: validators[1],
+                signer: validators[0],
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_seal_beneficiary_mismatch() {
+        let key = key(1);
+        let signer = Address::from_private_key(&key);
+        let mut header = sealed_header(10, &key);
+        header.beneficiary = Address::with_last_byte(0xbb);
+        // Re-sign so that only the beneficiary check fails
+        let (signature, recovery_id) = key
+            .sign_prehash_recoverable(header.aura_seal_hash().as_slice())
+            .unwrap();
+        let mut seal = [0u8; 65];
+        seal[..64].copy_from_slice(&signature.to_bytes());
+        seal[64] = recovery_id.to_byte();
+        header.aura_seal = Some(seal.into());
+        assert_eq!(
+            verify_seal(&header, &[signer]),
+            Err(SealError::BeneficiaryMismatch {
+                beneficiary: Address::with_last_byte(0xbb),
+                signer,
+            })
+        );
+    }
+
+    #[test]
+    fn test_seal_header() {
+        let key = key(3);
+        let unsealed = GnosisHeader {
+            number: 1,
+            beneficiary: Address::with_last_byte(0xbb),
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        let header = seal_header(unsealed, U256::from(100), &key).unwrap();
+        let signer = Address::from_private_key(&key);
+        assert!(header.is_pre_merge());
+        assert_eq!(header.beneficiary, signer);
+        assert_eq!(header.aura_step, Some(U256::from(100)));
+        assert_eq!(recover_seal_signer(&header), Ok(signer));
+        assert_eq!(verify_seal(&header, &[signer]), Ok(signer));
+
+        // The step is not signed, so resealing for another step yields the same signature
+        let resealed = seal_header(header.clone(), U256::from(101), &key).unwrap();
+        assert_eq!(resealed.aura_seal, header.aura_seal);
+        assert_eq!(resealed.aura_step, Some(U256::from(101)));
+        assert_ne!(resealed.hash_slow(), header.hash_slow());
+    }
+
+    #[test]
+    fn test_seal_header_rejects_post_merge() {
+        let header = GnosisHeader {
+            mix_hash: Some(B256::ZERO),
+            nonce: Some(B64::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(
+            seal_header(header, U256::from(1), &key(1)),
+            Err(SealError::PostMerge)
+        );
+    }
+}
@@ -0,0 +1,544 @@
+use alloy_primitives::{Address, B256, BlockNumber, Bloom, Bytes, U256};
+use sha2::{Digest, Sha256};
+
+use crate::header::GnosisHeader;
+
+/// Depth of the `execution_payload` field in a beacon block body, from Capella onward.
+pub const EXECUTION_PAYLOAD_DEPTH: usize = 4;
+
+/// Index of the `execution_payload` field in a beacon block body.
+pub const EXECUTION_PAYLOAD_INDEX: u64 = 9;
+
+/// Maximum length of the `extra_data` of an execution payload.
+pub const MAX_EXTRA_DATA_BYTES: usize = 32;
+
+/// Errors returned when verifying an execution header against a beacon block.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum BeaconProofError {
+    /// The header was sealed by AuRa and has no execution payload.
+    #[display("block {_0} is pre-merge")]
+    PreMerge(#[error(not(source))] BlockNumber),
+    /// The withdrawals root was given for a header without withdrawals, or the other way round.
+    #[display("withdrawals root does not match the fork of the header")]
+    WithdrawalsRootMismatch,
+    /// The extra data is longer than an execution payload allows.
+    #[display("extra data is {_0} bytes")]
+    ExtraDataTooLong(#[error(not(source))] usize),
+    /// The branch does not lead from the execution payload to the body root.
+    #[display("invalid execution payload branch")]
+    InvalidBranch,
+    /// The execution payload is not the one of the header.
+    #[display("execution payload has block hash {payload}, header hashes to {header}")]
+    BlockHashMismatch {
+        /// Block hash in the execution payload.
+        payload: B256,
+        /// Hash of the header.
+        header: B256,
+    },
+}
+
+/// The SSZ `ExecutionPayloadHeader` of the beacon chain.
+///
+/// The same type covers every fork since Bellatrix: `withdrawals_root` is present from Capella
+/// onward and the blob gas fields from Deneb onward. Its `hash_tree_root` is also the root of the
+/// full `ExecutionPayload`, since the transactions and withdrawals only enter it through their
+/// roots.
+///
+/// Unlike the execution header, the payload commits to the transactions and withdrawals with SSZ
+/// roots rather than trie roots, and to the withdrawals in beacon units.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExecutionPayloadHeader {
+    /// Hash of the parent block.
+    pub parent_hash: B256,
+    /// Beneficiary of the block.
+    pub fee_recipient: Address,
+    /// State root after the block.
+    pub state_root: B256,
+    /// Receipts root of the block.
+    pub receipts_root: B256,
+    /// Bloom filter of the logs of the block.
+    pub logs_bloom: Bloom,
+    /// RANDAO mix, the `mix_hash` of the execution header.
+    pub prev_randao: B256,
+    /// Number of the block.
+    pub block_number: u64,
+    /// Gas limit of the block.
+    pub gas_limit: u64,
+    /// Gas used by the block.
+    pub gas_used: u64,
+    /// Timestamp of the block.
+    pub timestamp: u64,
+    /// Extra data of the block, at most [`MAX_EXTRA_DATA_BYTES`] long.
+    pub extra_data: Bytes,
+    /// Base fee of the block.
+    pub base_fee_per_gas: U256,
+    /// Hash of the execution header.
+    pub block_hash: B256,
+    /// SSZ root of the transactions list.
+    pub transactions_root: B256,
+    /// SSZ root of the withdrawals list, from Capella onward.
+    pub withdrawals_root: Option<B256>,
+    /// Blob gas used by the block, from Deneb onward.
+    pub blob_gas_used: Option<u64>,
+    /// Excess blob gas of the block, from Deneb onward.
+    pub excess_blob_gas: Option<u64>,
+}
+
+impl ExecutionPayloadHeader {
+    /// Builds the payload header of a post-merge header, given the SSZ roots of its transactions
+    /// and withdrawals.
+    ///
+    /// `withdrawals_root` must be given exactly for headers with withdrawals.
+    pub fn from_header(
+        header: &GnosisHeader,
+        transactions_root: B256,
+        withdrawals_root: Option<B256>,
+    ) -> Result<Self, BeaconProofError> {
+        if !header.is_post_merge() {
+            return Err(BeaconProofError::PreMerge(header.number));
+        }
+        if header.withdrawals_root.is_some() != withdrawals_root.is_some() {
+            return Err(BeaconProofError::WithdrawalsRootMismatch);
+        }
+        if header.extra_data.len() > MAX_EXTRA_DATA_BYTES {
+            return Err(BeaconProofError::ExtraDataTooLong(header.extra_data.len()));
+        }
+        Ok(Self {
+            parent_hash: header.parent_hash,
+            fee_recipient: header.beneficiary,
+            state_root: header.state_root,
+            receipts_root: header.receipts_root,
+            logs_bloom: header.logs_bloom,
+            prev_randao: header.mix_hash.unwrap_or_default(),
+            block_number: header.number,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: header.timestamp,
+            extra_data: header.extra_data.clone(),
+            base_fee_per_gas: U256::from(header.base_fee_per_gas.unwrap_or_default()),
+            block_hash: header.hash_slow(),
+            transactions_root,
+            withdrawals_root,
+            blob_gas_used: header.blob_gas_used,
+            excess_blob_gas: header.excess_blob_gas,
+        })
+    }
+
+    /// Computes the SSZ `hash_tree_root`.
+    ///
+    /// Fails if the extra data is longer than [`MAX_EXTRA_DATA_BYTES`], since no payload can
+    /// hold it.
+    pub fn hash_tree_root(&self) -> Result<B256, BeaconProofError> {
+        let len = self.extra_data.len();
+        if len > MAX_EXTRA_DATA_BYTES {
+            return Err(BeaconProofError::ExtraDataTooLong(len));
+        }
+        let mut extra_data = [0; MAX_EXTRA_DATA_BYTES];
+        extra_data[..len].copy_from_slice(&self.extra_data);
+
+        let mut fields = vec![
+            self.parent_hash,
+            B256::right_padding_from(self.fee_recipient.as_slice()),
+            self.state_root,
+            self.receipts_root,
+            merkleize(
+                &self
+                    .logs_bloom
+                    .as_slice()
+                    .chunks(32)
+                    .map(B256::from_slice)
+                    .collect::<Vec<_>>(),
+                3,
+            ),
+            self.prev_randao,
+            uint64_root(self.block_number),
+            uint64_root(self.gas_limit),
+            uint64_root(self.gas_used),
+            uint64_root(self.timestamp),
+            mix_in_length(B256::from(extra_data), len as u64),
+            B256::from(self.base_fee_per_gas.to_le_bytes::<32>()),
+            self.block_hash,
+            self.transactions_root,
+        ];
+        fields.extend(self.withdrawals_root);
+        fields.extend(self.blob_gas_used.map(uint64_root));
+        fields.extend(self.excess_blob_gas.map(uint64_root));
+        Ok(merkleize(
+            &fields,
+            fields.len().next_power_of_two().trailing_zeros() as usize,
+        ))
+    }
+}
+
+/// The SSZ `BeaconBlockHeader`, as attested by the sync committee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BeaconBlockHeader {
+    /// Slot of the block.
+    pub slot: u64,
+    /// Index of the validator that proposed the block.
+    pub proposer_index: u64,
+    /// Root of the parent beacon block.
+    pub parent_root: B256,
+    /// Root of the beacon state after the block.
+    pub state_root: B256,
+    /// Root of the block body.
+    pub body_root: B256,
+}
+
+impl BeaconBlockHeader {
+    /// Computes the SSZ `hash_tree_root`, the beacon block root.
+    ///
+    /// This is the `parent_beacon_block_root` of the execution header built on top of the
+    /// block from Deneb onward.
+    pub fn hash_tree_root(&self) -> B256 {
+        merkleize(
+            &[
+                uint64_root(self.slot),
+                uint64_root(self.proposer_index),
+                self.parent_root,
+                self.state_root,
+                self.body_root,
+            ],
+            3,
+        )
+    }
+}
+
+/// The execution part of a light client header: a trusted beacon block header, the execution
+/// payload header of its body, and the Merkle branch linking them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightClientExecution {
+    /// The beacon block header, trusted through the sync committee.
+    pub beacon: BeaconBlockHeader,
+    /// The execution payload header of the block.
+    pub execution: ExecutionPayloadHeader,
+    /// Siblings on the path from the execution payload to the body root, lowest first.
+    pub execution_branch: [B256; EXECUTION_PAYLOAD_DEPTH],
+}
+
+impl LightClientExecution {
+    /// Checks that the execution payload header is part of the beacon block body.
+    ///
+    /// Fails with [`BeaconProofError::ExtraDataTooLong`] if the payload header cannot be hashed.
+    pub fn verify_branch(&self) -> Result<(), BeaconProofError> {
+        if !is_valid_merkle_branch(
+            self.execution.hash_tree_root()?,
+            &self.execution_branch,
+            EXECUTION_PAYLOAD_INDEX,
+            self.beacon.body_root,
+        ) {
+            return Err(BeaconProofError::InvalidBranch);
+        }
+        Ok(())
+    }
+
+    /// Checks that `header` is the execution block of the trusted beacon block.
+    ///
+    /// The header is trusted as much as the beacon block header once this passes, since the
+    /// block hash commits to every field of the header.
+    pub fn verify_header(&self, header: &GnosisHeader) -> Result<(), BeaconProofError> {
+        self.verify_branch()?;
+        let hash = header.hash_slow();
+        if self.execution.block_hash != hash {
+            return Err(BeaconProofError::BlockHashMismatch {
+                payload: self.execution.block_hash,
+                header: hash,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Checks a Merkle branch as in the consensus specs' `is_valid_merkle_branch`.
+///
+/// `index` is the position of `leaf` among the `2^branch.len()` leaves under `root`, and the
+/// branch lists the siblings from the leaf upward.
+pub fn is_valid_merkle_branch(leaf: B256, branch: &[B256], index: u64, root: B256) -> bool {
+    if branch.len() < u64::BITS as usize && index >> branch.len() != 0 {
+        return false;
+    }
+    let computed = branch
+        .iter()
+        .enumerate()
+        .fold(leaf, |node, (depth, sibling)| {
+            if (index >> depth) & 1 == 1 {
+                sha256_pair(*sibling, node)
+            } else {
+                sha256_pair(node, *sibling)
+            }
+        });
+    computed == root
+}
+
+fn uint64_root(value: u64) -> B256 {
+    B256::right_padding_from(&value.to_le_bytes())
+}
+
+fn mix_in_length(root: B256, length: u64) -> B256 {
+    sha256_pair(root, uint64_root(length))
+}
+
+/// Merkleizes `chunks` into a tree of the given depth, padding with zero chunks.
+fn merkleize(chunks: &[B256], depth: usize) -> B256 {
+    let mut layer = chunks.to_vec();
+    let mut zero = B256::ZERO;
+    for _ in 0..depth {
+        if layer.len() % 2 == 1 {
+            layer.push(zero);
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| sha256_pair(pair[0], pair[1]))
+            .collect();
+        zero = sha256_pair(zero, zero);
+    }
+    layer.first().copied().unwrap_or(zero)
+}
+
+fn sha256_pair(left: B256, right: B256) -> B256 {
+    B256::new(
+        Sha256::new()
+            .chain_update(left)
+            .chain_update(right)
+            .finalize()
+            .into(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestChainGenerator;
+    use alloy_primitives::b256;
+
+    /// A post-merge Cancun header from the test chain.
+    fn cancun_header() -> GnosisHeader {
+        TestChainGenerator::default()
+            .headers(18)
+            .into_iter()
+            .find(|header| header.is_post_merge() && header.blob_gas_used.is_some())
+            .unwrap()
+    }
+
+    /// A beacon block body with the given execution payload root, and the branch proving it.
+    fn body_with_payload(payload_root: B256) -> (B256, [B256; EXECUTION_PAYLOAD_DEPTH]) {
+        let mut leaves: Vec<_> = (0..16).map(|i| B256::with_last_byte(i + 1)).collect();
+        leaves[EXECUTION_PAYLOAD_INDEX as usize] = payload_root;
+
+        let mut branch = [B256::ZERO; EXECUTION_PAYLOAD_DEPTH];
+        let (mut layer, mut index) = (leaves, EXECUTION_PAYLOAD_INDEX as usize);
+        for sibling in &mut branch {
+            *sibling = layer[index ^ 1];
+            layer = layer
+                .chunks(2)
+                .map(|pair| sha256_pair(pair[0], pair[1]))
+                .collect();
+            index /= 2;
+        }
+        (layer[0], branch)
+    }
+
+    #[test]
+    fn test_merkleize() {
+        let zero_1 = b256!("f5a5fd42d16a20302798ef6ed309979b43003d2320d9f0e8ea9831a92759fb4b");
+        assert_eq!(merkleize(&[], 1), zero_1);
+        assert_eq!(merkleize(&[B256::ZERO], 1), zero_1);
+        assert_eq!(
+            merkleize(&[B256::ZERO], 2),
+            b256!("db56114e00fdd4c1f85c892bf35ac9a89289aaecb1ebd0a96cde606a748b5d71")
+        );
+        assert_eq!(
+            BeaconBlockHeader::default().hash_tree_root(),
+            merkleize(&[], 3)
+        );
+    }
+
+    /// Roots computed with a separate implementation of `hash_tree_root` written from the SSZ
+    /// specification, which packs the serialized fields of each type instead of special-casing
+    /// them.
+    #[test]
+    fn test_hash_tree_root_known_answers() {
+        let deneb = ExecutionPayloadHeader {
+            parent_hash: B256::repeat_byte(0x01),
+            fee_recipient: Address::repeat_byte(0x02),
+            state_root: B256::repeat_byte(0x03),
+            receipts_root: B256::repeat_byte(0x04),
+            logs_bloom: Bloom::from_slice(&(0..=255).collect::<Vec<u8>>()),
+            prev_randao: B256::repeat_byte(0x05),
+            block_number: 17_000_000,
+            gas_limit: 30_000_000,
+            gas_used: 12_345_678,
+            timestamp: 1_700_000_000,
+            extra_data: Bytes::from_static(b"gnosis extra data"),
+            base_fee_per_gas: U256::from(7_000_000_001u64),
+            block_hash: B256::repeat_byte(0x06),
+            transactions_root: B256::repeat_byte(0x07),
+            withdrawals_root: Some(B256::repeat_byte(0x08)),
+            blob_gas_used: Some(393_216),
+            excess_blob_gas: Some(1_048_576),
+        };
+        assert_eq!(
+            deneb.hash_tree_root(),
+            Ok(b256!(
+                "f7589f56fb211c5c921f4d431f0d5d6220a50c0d7ad81000d47ccba9fcbc87b3"
+            ))
+        );
+        let capella = ExecutionPayloadHeader {
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            ..deneb.clone()
+        };
+        assert_eq!(
+            capella.hash_tree_root(),
+            Ok(b256!(
+                "ab8c7d1c7142749c1fe034ac4c44b93c0fe61a3ec56c69c77dfdb8c36b87fb07"
+            ))
+        );
+        let bellatrix = ExecutionPayloadHeader {
+            withdrawals_root: None,
+            ..capella
+        };
+        assert_eq!(
+            bellatrix.hash_tree_root(),
+            Ok(b256!(
+                "5b327f6879dd7ae9ab81b22bc14356b95862e974ab20315e425d3954b5c32f16"
+            ))
+        );
+        let full_extra_data = ExecutionPayloadHeader {
+            extra_data: Bytes::from(vec![0xee; MAX_EXTRA_DATA_BYTES]),
+            ..Default::default()
+        };
+        assert_eq!(
+            full_extra_data.hash_tree_root(),
+            Ok(b256!(
+                "d21685bae21ed2aed457bafc0742637922e7c2150b49ab5da4151d3680fbb69c"
+            ))
+        );
+
+        let beacon = BeaconBlockHeader {
+            slot: 8_000_000,
+            proposer_index: 123_456,
+            parent_root: B256::repeat_byte(0x0a),
+            state_root: B256::repeat_byte(0x0b),
+            body_root: B256::repeat_byte(0x0c),
+        };
+        assert_eq!(
+            beacon.hash_tree_root(),
+            b256!("141a05d8e13722adf75a19659fa3c9fa32d3a955b74097f931db45fe84e7292e")
+        );
+        assert_eq!(
+            BeaconBlockHeader::default().hash_tree_root(),
+            b256!("c78009fdf07fc56a11f122370658a353aaa542ed63e44c4bc15ff4cd105ab33c")
+        );
+    }
+
+    #[test]
+    fn test_payload_header_fields() {
+        let header = cancun_header();
+        let payload =
+            ExecutionPayloadHeader::from_header(&header, B256::repeat_byte(1), Some(B256::ZERO))
+                .unwrap();
+        assert_eq!(payload.block_hash, header.hash_slow());
+        assert_eq!(payload.prev_randao, header.mix_hash.unwrap());
+        assert_eq!(payload.excess_blob_gas, header.excess_blob_gas);
+
+        // Every field, including the optional ones, is committed to
+        let root = payload.hash_tree_root().unwrap();
+        let capella = ExecutionPayloadHeader {
+            blob_gas_used: None,
+            excess_blob_gas: None,
+            ..payload.clone()
+        };
+        assert_ne!(capella.hash_tree_root().unwrap(), root);
+        let other_extra_data = ExecutionPayloadHeader {
+            extra_data: Bytes::from_static(&[0]),
+            ..payload.clone()
+        };
+        assert_ne!(other_extra_data.hash_tree_root().unwrap(), root);
+        let too_long = ExecutionPayloadHeader {
+            extra_data: Bytes::from(vec![0; MAX_EXTRA_DATA_BYTES + 1]),
+            ..payload.clone()
+        };
+        assert_eq!(
+            too_long.hash_tree_root(),
+            Err(BeaconProofError::ExtraDataTooLong(33))
+        );
+
+        assert_eq!(
+            ExecutionPayloadHeader::from_header(&header, B256::ZERO, None),
+            Err(BeaconProofError::WithdrawalsRootMismatch)
+        );
+        assert_eq!(
+            ExecutionPayloadHeader::from_header(&GnosisHeader::default(), B256::ZERO, None),
+            Err(BeaconProofError::PreMerge(0))
+        );
+    }
+
+    #[test]
+    fn test_verify_header() {
+        let header = cancun_header();
+        let execution =
+            ExecutionPayloadHeader::from_header(&header, B256::repeat_byte(1), Some(B256::ZERO))
+                .unwrap();
+        let (body_root, execution_branch) = body_with_payload(execution.hash_tree_root().unwrap());
+        let proof = LightClientExecution {
+            beacon: BeaconBlockHeader {
+                slot: 100,
+                body_root,
+                ..Default::default()
+            },
+            execution,
+            execution_branch,
+        };
+        assert_eq!(proof.verify_header(&header), Ok(()));
+
+        let mut other = header.clone();
+        other.gas_used += 1;
+        assert!(matches!(
+            proof.verify_header(&other),
+            Err(BeaconProofError::BlockHashMismatch { .. })
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.execution.gas_used += 1;
+        assert_eq!(
+            tampered.verify_header(&header),
+            Err(BeaconProofError::InvalidBranch)
+        );
+        let mut tampered = proof.clone();
+        tampered.execution_branch[3] = B256::ZERO;
+        assert_eq!(
+            tampered.verify_branch(),
+            Err(BeaconProofError::InvalidBranch)
+        );
+        let mut tampered = proof;
+        tampered.execution.extra_data = Bytes::from(vec![0; 40]);
+        assert_eq!(
+            tampered.verify_branch(),
+            Err(BeaconProofError::ExtraDataTooLong(40))
+        );
+    }
+
+    #[test]
+    fn test_merkle_branch_index() {
+        let (root, branch) = body_with_payload(B256::repeat_byte(7));
+        assert!(is_valid_merkle_branch(
+            B256::repeat_byte(7),
+            &branch,
+            9,
+            root
+        ));
+        assert!(!is_valid_merkle_branch(
+            B256::repeat_byte(7),
+            &branch,
+            8,
+            root
+        ));
+        assert!(!is_valid_merkle_branch(
+            B256::repeat_byte(7),
+            &branch,
+            25,
+            root
+        ));
+    }
+}
@@ -0,0 +1,265 @@
+use alloy_eips::{eip7594::MAX_BLOBS_PER_TX_FUSAKA, eip7840, eip7840::BlobParams};
+use alloy_genesis::ChainConfig;
+use reth_tracing::tracing::debug;
+
+use crate::header::GnosisHeader;
+
+/// Blob parameters used by Gnosis Chain since Cancun.
+///
+/// Gnosis targets one blob per block with a maximum of two, and enforces a minimum blob base fee
+/// of 1 gwei.
+pub const GNOSIS_BLOB_PARAMS: BlobParams = BlobParams {
+    target_blob_count: 1,
+    max_blob_count: 2,
+    update_fraction: 1_112_826,
+    min_blob_fee: 1_000_000_000,
+    max_blobs_per_tx: 2,
+    blob_base_cost: 0,
+};
+
+/// Timestamp-activated schedule of [`BlobParams`].
+///
+/// Each entry takes effect at its activation timestamp and stays in effect until the next entry.
+/// This covers both blob parameters introduced by full hardforks (Cancun, Prague, Osaka) and
+/// blob-parameter-only forks (`bpo1`..`bpo5`) as defined in EIP-7892.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlobSchedule {
+    /// Activation timestamps and their parameters, sorted by timestamp.
+    entries: Vec<(u64, BlobParams)>,
+}
+
+impl BlobSchedule {
+    /// Creates a schedule from `(activation timestamp, params)` pairs.
+    ///
+    /// Entries don't need to be sorted. If two entries share a timestamp, the last one wins.
+    pub fn new(entries: impl IntoIterator<Item = (u64, BlobParams)>) -> Self {
+        let mut schedule = Self::default();
+        for (timestamp, params) in entries {
+            schedule.insert(timestamp, params);
+        }
+        schedule
+    }
+
+    /// Returns the blob schedule of Gnosis Chain mainnet.
+    pub fn gnosis() -> Self {
+        Self::new([
+            // Cancun
+            (1_710_181_820, GNOSIS_BLOB_PARAMS),
+            // Prague
+            (1_746_021_820, GNOSIS_BLOB_PARAMS),
+        ])
+    }
+
+    /// Returns the blob schedule of the Chiado testnet.
+    pub fn chiado() -> Self {
+        Self::new([
+            // Cancun
+            (1_706_724_940, GNOSIS_BLOB_PARAMS),
+            // Prague
+            (1_741_254_220, GNOSIS_BLOB_PARAMS),
+        ])
+    }
+
+    /// Builds the schedule from the `blobSchedule` section of a chain spec config.
+    ///
+    /// Each entry is keyed by fork name and activated at the matching fork timestamp. Entries
+    /// whose fork has no timestamp configured are ignored. As with Ethereum, entries from Osaka
+    /// onward charge [`eip7840::BLOB_BASE_COST`] and cap blobs per transaction at
+    /// [`MAX_BLOBS_PER_TX_FUSAKA`].
+    ///
+    /// The config format has no field for the minimum blob fee, so every entry uses the Gnosis
+    /// minimum from [`GNOSIS_BLOB_PARAMS`].
+    pub fn from_chain_config(config: &ChainConfig) -> Self {
+        let mut schedule = Self::default();
+        for (fork, params) in &config.blob_schedule {
+            let timestamp = match fork.as_str() {
+                "cancun" => config.cancun_time,
+                "prague" => config.prague_time,
+                "osaka" => config.osaka_time,
+                "bpo1" => config.bpo1_time,
+                "bpo2" => config.bpo2_time,
+                "bpo3" => config.bpo3_time,
+                "bpo4" => config.bpo4_time,
+                "bpo5" => config.bpo5_time,
+                _ => {
+                    debug!(fork, "Ignoring unknown blob schedule entry");
+                    None
+                }
+            };
+            let Some(timestamp) = timestamp else {
+                continue;
+            };
+
+            let params = BlobParams {
+                min_blob_fee: GNOSIS_BLOB_PARAMS.min_blob_fee,
+                ..*params
+            };
+            let params = match fork.as_str() {
+                "cancun" | "prague" => params,
+                _ => params
+                    .with_blob_base_cost(eip7840::BLOB_BASE_COST)
+                    .with_max_blobs_per_tx(MAX_BLOBS_PER_TX_FUSAKA.min(params.max_blob_count)),
+            };
+            schedule.insert(timestamp, params);
+        }
+        schedule
+    }
+
+    /// Adds an entry activated at `timestamp`, replacing any entry with the same timestamp.
+    pub fn insert(&mut self, timestamp: u64, params: BlobParams) {
+        match self.entries.binary_search_by_key(&timestamp, |(ts, _)| *ts) {
+            Ok(idx) => self.entries[idx].1 = params,
+            Err(idx) => self.entries.insert(idx, (timestamp, params)),
+        }
+    }
+
+    /// Returns the entries of the schedule, sorted by activation timestamp.
+    pub fn entries(&self) -> &[(u64, BlobParams)] {
+        &self.entries
+    }
+
+    /// Returns `true` if the schedule has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the [`BlobParams`] in effect at the given timestamp.
+    ///
+    /// Returns `None` if no entry has been activated yet.
+    pub fn params_at_timestamp(&self, timestamp: u64) -> Option<BlobParams> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(ts, _)| timestamp >= *ts)
+            .map(|(_, params)| *params)
+    }
+
+    /// Returns the [`BlobParams`] in effect for the given header.
+    ///
+    /// Returns `None` if the header predates Cancun or no entry is active at its timestamp.
+    pub fn params_for_header(&self, header: &GnosisHeader) -> Option<BlobParams> {
+        if !header.cancun_active() {
+            return None;
+        }
+        self.params_at_timestamp(header.timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+
+    fn bpo_params(max_blob_count: u64) -> BlobParams {
+        BlobParams {
+            target_blob_count: max_blob_count / 2,
+            max_blob_count,
+            ..GNOSIS_BLOB_PARAMS
+        }
+    }
+
+    fn cancun_header(timestamp: u64) -> GnosisHeader {
+        GnosisHeader {
+            timestamp,
+            mix_hash: Some(B256::ZERO),
+            nonce: Some(Default::default()),
+            base_fee_per_gas: Some(1_000_000_000),
+            withdrawals_root: Some(B256::ZERO),
+            blob_gas_used: Some(131_072),
+            excess_blob_gas: Some(262_144),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_params_at_timestamp() {
+        let schedule = BlobSchedule::new([(200, bpo_params(4)), (100, GNOSIS_BLOB_PARAMS)]);
+        assert_eq!(schedule.entries()[0].0, 100);
+
+        assert_eq!(schedule.params_at_timestamp(99), None);
+        assert_eq!(schedule.params_at_timestamp(100), Some(GNOSIS_BLOB_PARAMS));
+        assert_eq!(schedule.params_at_timestamp(199), Some(GNOSIS_BLOB_PARAMS));
+        assert_eq!(schedule.params_at_timestamp(200), Some(bpo_params(4)));
+        assert_eq!(schedule.params_at_timestamp(u64::MAX), Some(bpo_params(4)));
+    }
+
+    #[test]
+    fn test_insert_replaces_same_timestamp() {
+        let mut schedule = BlobSchedule::new([(100, GNOSIS_BLOB_PARAMS)]);
+        schedule.insert(100, bpo_params(6));
+        assert_eq!(schedule.entries().len(), 1);
+        assert_eq!(schedule.params_at_timestamp(100), Some(bpo_params(6)));
+    }
+
+    #[test]
+    fn test_params_for_header() {
+        let schedule = BlobSchedule::gnosis();
+        let header = cancun_header(1_746_021_820);
+        assert_eq!(
+            schedule.params_for_header(&header),
+            Some(GNOSIS_BLOB_PARAMS)
+        );
+
+        let mut pre_cancun = header;
+        pre_cancun.blob_gas_used = None;
+        pre_cancun.excess_blob_gas = None;
+        assert_eq!(schedule.params_for_header(&pre_cancun), None);
+    }
+
+    #[test]
+    fn test_from_chain_config() {
+        let mut config = ChainConfig {
+            cancun_time: Some(100),
+            prague_time: Some(200),
+            bpo1_time: Some(300),
+            ..Default::default()
+        };
+        config
+            .blob_schedule
+            .insert("cancun".to_string(), GNOSIS_BLOB_PARAMS);
+        config
+            .blob_schedule
+            .insert("prague".to_string(), GNOSIS_BLOB_PARAMS);
+        config
+            .blob_schedule
+            .insert("bpo1".to_string(), bpo_params(4));
+        // No activation time configured, must be ignored
+        config
+            .blob_schedule
+            .insert("bpo2".to_string(), bpo_params(8));
+
+        let schedule = BlobSchedule::from_chain_config(&config);
+        assert_eq!(schedule.entries().len(), 3);
+        assert_eq!(schedule.params_at_timestamp(150), Some(GNOSIS_BLOB_PARAMS));
+
+        let bpo1 = schedule.params_at_timestamp(300).unwrap();
+        assert_eq!(bpo1.max_blob_count, 4);
+        assert_eq!(bpo1.blob_base_cost, eip7840::BLOB_BASE_COST);
+        assert_eq!(bpo1.max_blobs_per_tx, 4);
+    }
+
+    #[test]
+    fn test_from_chain_config_json() {
+        let config: ChainConfig = serde_json::from_str(
+            r#"{
+                "chainId": 100,
+                "cancunTime": 1710181820,
+                "pragueTime": 1746021820,
+                "blobSchedule": {
+                    "cancun": { "target": 1, "max": 2, "baseFeeUpdateFraction": 1112826 },
+                    "prague": { "target": 1, "max": 2, "baseFeeUpdateFraction": 1112826 }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let schedule = BlobSchedule::from_chain_config(&config);
+        let params = schedule.params_at_timestamp(1_746_021_820).unwrap();
+        assert_eq!(params.max_blob_count, 2);
+        assert_eq!(params.target_blob_count, 1);
+        assert_eq!(params.update_fraction, 1_112_826);
+        assert_eq!(params.min_blob_fee, GNOSIS_BLOB_PARAMS.min_blob_fee);
+        assert_eq!(schedule.params_at_timestamp(1_710_181_819), None);
+    }
+}
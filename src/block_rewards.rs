@@ -0,0 +1,306 @@
+use std::collections::BTreeMap;
+
+use alloy_eips::eip4788::SYSTEM_ADDRESS;
+use alloy_primitives::{Address, BlockNumber, Bytes, U256, address};
+use alloy_sol_types::{SolCall, sol};
+
+use crate::{header::GnosisHeader, withdrawals::SYSTEM_CALL_GAS_LIMIT};
+
+sol! {
+    /// Mints block rewards and bridged xDAI, called by the system at the end of each block.
+    function reward(address[] benefactors, uint16[] kind)
+        returns (address[] receiversNative, uint256[] rewardsNative);
+}
+
+/// Kind of a benefactor passed to the block reward contract.
+///
+/// The numeric values match the ones used by OpenEthereum and Nethermind.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RewardKind {
+    /// The author of the block.
+    Author,
+    /// The author of an uncle, at the given distance from the block.
+    Uncle(u8),
+    /// The author of an empty step.
+    EmptyStep,
+    /// A reward attributed by an external protocol.
+    External,
+}
+
+impl RewardKind {
+    /// Returns the value passed in the `kind` array of the contract call.
+    pub const fn as_u16(self) -> u16 {
+        match self {
+            Self::Author => 0,
+            Self::Uncle(distance) => 100 + distance as u16,
+            Self::EmptyStep => 2,
+            Self::External => 3,
+        }
+    }
+}
+
+/// Block reward contract addresses by activation block, as configured in the chain spec.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlockRewardContracts {
+    transitions: BTreeMap<BlockNumber, Address>,
+}
+
+impl BlockRewardContracts {
+    /// Creates the lookup from `(activation block, contract address)` pairs.
+    pub fn new(transitions: impl IntoIterator<Item = (BlockNumber, Address)>) -> Self {
+        Self {
+            transitions: transitions.into_iter().collect(),
+        }
+    }
+
+    /// Returns the block reward contracts of Gnosis Chain mainnet.
+    pub fn gnosis() -> Self {
+        Self::new([
+            (1_310, address!("867305d19606aadba405ce534e303d0e225f9556")),
+            (
+                9_186_425,
+                address!("481c034c6d9441db23ea48de68bcae812c5d39ba"),
+            ),
+        ])
+    }
+
+    /// Returns the block reward contracts of the Chiado testnet.
+    pub fn chiado() -> Self {
+        Self::new([(0, address!("2000000000000000000000000000000000000001"))])
+    }
+
+    /// Adds a contract that takes over from the given block onward.
+    pub fn insert(&mut self, block: BlockNumber, contract: Address) {
+        self.transitions.insert(block, contract);
+    }
+
+    /// Returns the contract in charge of rewards at the given block.
+    ///
+    /// Returns `None` before the first contract transition.
+    pub fn address_at(&self, block: BlockNumber) -> Option<Address> {
+        self.transitions
+            .range(..=block)
+            .next_back()
+            .map(|(_, address)| *address)
+    }
+
+    /// Returns the transitions, ordered by activation block.
+    pub fn transitions(&self) -> impl Iterator<Item = (BlockNumber, Address)> + '_ {
+        self.transitions
+            .iter()
+            .map(|(block, address)| (*block, *address))
+    }
+}
+
+/// The `reward` system call made to the block reward contract at the end of a block.
+///
+/// The call is made both for AuRa and post-merge blocks, with the block's beneficiary as the only
+/// benefactor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockRewardSystemCall {
+    /// Sender of the call.
+    pub caller: Address,
+    /// The block reward contract.
+    pub to: Address,
+    /// Gas limit of the call.
+    pub gas_limit: u64,
+    /// ABI encoded `reward` calldata.
+    pub input: Bytes,
+}
+
+impl BlockRewardSystemCall {
+    /// Builds the call rewarding the given benefactors.
+    pub fn new(contract: Address, benefactors: &[(Address, RewardKind)]) -> Self {
+        let call = rewardCall {
+            benefactors: benefactors.iter().map(|(address, _)| *address).collect(),
+            kind: benefactors.iter().map(|(_, kind)| kind.as_u16()).collect(),
+        };
+        Self {
+            caller: SYSTEM_ADDRESS,
+            to: contract,
+            gas_limit: SYSTEM_CALL_GAS_LIMIT,
+            input: call.abi_encode().into(),
+        }
+    }
+
+    /// Builds the call for `header`, with its beneficiary rewarded as the author.
+    ///
+    /// Returns `None` if no block reward contract is active at the header's block.
+    pub fn for_header(header: &GnosisHeader, contracts: &BlockRewardContracts) -> Option<Self> {
+        let contract = contracts.address_at(header.number)?;
+        Some(Self::new(
+            contract,
+            &[(header.beneficiary, RewardKind::Author)],
+        ))
+    }
+
+    /// Decodes the calldata of a `reward` call.
+    pub fn decode_input(input: &[u8]) -> alloy_sol_types::Result<rewardCall> {
+        rewardCall::abi_decode(input)
+    }
+}
+
+/// Errors returned when decoding the output of the block reward contract.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum BlockRewardsError {
+    /// The output is not a valid ABI encoding of the return values.
+    #[display("invalid reward output: {_0}")]
+    InvalidOutput(#[error(not(source))] String),
+    /// The contract returned a different number of receivers and amounts.
+    #[display("reward output length mismatch: {receivers} receivers, {amounts} amounts")]
+    LengthMismatch {
+        /// Number of receivers.
+        receivers: usize,
+        /// Number of amounts.
+        amounts: usize,
+    },
+}
+
+/// Native coin minted by the block reward contract for a single block.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlockRewards {
+    /// Receivers and the amounts minted to them, in the order returned by the contract.
+    pub mints: Vec<(Address, U256)>,
+}
+
+impl BlockRewards {
+    /// Decodes the return data of the `reward` call.
+    pub fn decode(output: &[u8]) -> Result<Self, BlockRewardsError> {
+        let ret = rewardCall::abi_decode_returns(output)
+            .map_err(|err| BlockRewardsError::InvalidOutput(err.to_string()))?;
+        if ret.receiversNative.len() != ret.rewardsNative.len() {
+            return Err(BlockRewardsError::LengthMismatch {
+                receivers: ret.receiversNative.len(),
+                amounts: ret.rewardsNative.len(),
+            });
+        }
+        Ok(Self {
+            mints: ret
+                .receiversNative
+                .into_iter()
+                .zip(ret.rewardsNative)
+                .collect(),
+        })
+    }
+
+    /// Returns the total amount minted in the block.
+    pub fn total(&self) -> U256 {
+        self.mints.iter().map(|(_, amount)| *amount).sum()
+    }
+
+    /// Returns the amounts minted per address, merging repeated receivers.
+    pub fn per_address(&self) -> BTreeMap<Address, U256> {
+        let mut totals = BTreeMap::new();
+        for (address, amount) in &self.mints {
+            *totals.entry(*address).or_insert(U256::ZERO) += *amount;
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+    use alloy_sol_types::SolValue;
+
+    #[test]
+    fn test_reward_kind_values() {
+        assert_eq!(RewardKind::Author.as_u16(), 0);
+        assert_eq!(RewardKind::EmptyStep.as_u16(), 2);
+        assert_eq!(RewardKind::External.as_u16(), 3);
+        assert_eq!(RewardKind::Uncle(1).as_u16(), 101);
+    }
+
+    #[test]
+    fn test_contract_lookup() {
+        let contracts = BlockRewardContracts::gnosis();
+        assert_eq!(contracts.address_at(0), None);
+        assert_eq!(
+            contracts.address_at(1_310),
+            Some(address!("867305d19606aadba405ce534e303d0e225f9556"))
+        );
+        assert_eq!(
+            contracts.address_at(9_186_424),
+            Some(address!("867305d19606aadba405ce534e303d0e225f9556"))
+        );
+        assert_eq!(
+            contracts.address_at(30_000_000),
+            Some(address!("481c034c6d9441db23ea48de68bcae812c5d39ba"))
+        );
+        assert_eq!(contracts.transitions().count(), 2);
+    }
+
+    #[test]
+    fn test_call_for_header() {
+        let header = GnosisHeader {
+            number: 100,
+            beneficiary: Address::with_last_byte(0xaa),
+            ..Default::default()
+        };
+        assert!(
+            BlockRewardSystemCall::for_header(&header, &BlockRewardContracts::gnosis()).is_none()
+        );
+
+        let call =
+            BlockRewardSystemCall::for_header(&header, &BlockRewardContracts::chiado()).unwrap();
+        assert_eq!(call.caller, SYSTEM_ADDRESS);
+        assert_eq!(
+            call.to,
+            address!("2000000000000000000000000000000000000001")
+        );
+        assert_eq!(
+            call.input[..4],
+            hex!("f91c2898"),
+            "selector of reward(address[],uint16[])"
+        );
+
+        let decoded = BlockRewardSystemCall::decode_input(&call.input).unwrap();
+        assert_eq!(decoded.benefactors, vec![header.beneficiary]);
+        assert_eq!(decoded.kind, vec![0]);
+    }
+
+    #[test]
+    fn test_decode_rewards() {
+        let receivers = vec![
+            Address::with_last_byte(1),
+            Address::with_last_byte(2),
+            Address::with_last_byte(1),
+        ];
+        let amounts = vec![U256::from(10), U256::from(20), U256::from(5)];
+        let output = (receivers, amounts).abi_encode_params();
+
+        let rewards = BlockRewards::decode(&output).unwrap();
+        assert_eq!(rewards.mints.len(), 3);
+        assert_eq!(rewards.total(), U256::from(35));
+        assert_eq!(
+            rewards.per_address()[&Address::with_last_byte(1)],
+            U256::from(15)
+        );
+    }
+
+    #[test]
+    fn test_decode_rewards_errors() {
+        let output = (vec![Address::with_last_byte(1)], Vec::<U256>::new()).abi_encode_params();
+        assert_eq!(
+            BlockRewards::decode(&output),
+            Err(BlockRewardsError::LengthMismatch {
+                receivers: 1,
+                amounts: 0
+            })
+        );
+
+        assert!(matches!(
+            BlockRewards::decode(&[0x01, 0x02]),
+            Err(BlockRewardsError::InvalidOutput(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_rewards() {
+        let output = (Vec::<Address>::new(), Vec::<U256>::new()).abi_encode_params();
+        let rewards = BlockRewards::decode(&output).unwrap();
+        assert!(rewards.mints.is_empty());
+        assert_eq!(rewards.total(), U256::ZERO);
+    }
+}
@@ -0,0 +1,287 @@
+use std::io::{self, Read, Write};
+
+use alloy_rlp::Encodable;
+
+use crate::{eth_wire::GnosisBlock, header::GnosisHeader};
+
+/// Default limit on the encoded size of a single block read from a chain file.
+pub const DEFAULT_MAX_BLOCK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Errors returned by the [`ChainFileReader`].
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+pub enum ChainFileError {
+    /// Reading the underlying file failed.
+    #[display("{_0}")]
+    Io(io::Error),
+    /// The block starting at `offset` could not be decoded.
+    #[display("invalid block at byte {offset}: {error}")]
+    Decode {
+        /// Offset of the first byte of the block.
+        offset: u64,
+        /// The decoding error.
+        error: alloy_rlp::Error,
+    },
+    /// The file ends in the middle of the block starting at `offset`.
+    #[display("truncated block at byte {_0}")]
+    Truncated(#[error(not(source))] u64),
+    /// The block starting at `offset` is larger than the configured limit.
+    #[display("block at byte {offset} is {size} bytes, more than the limit of {limit}")]
+    BlockTooLarge {
+        /// Offset of the first byte of the block.
+        offset: u64,
+        /// Encoded size of the block.
+        size: usize,
+        /// The configured limit.
+        limit: usize,
+    },
+}
+
+/// Streams blocks from a chain file, as exported by reth and Nethermind.
+///
+/// A chain file is a plain concatenation of RLP-encoded blocks. Only one block is held in memory
+/// at a time, and AuRa and post-merge blocks can follow each other freely. The reader stops after
+/// the first error, since the position of the next block is unknown.
+#[derive(Debug)]
+pub struct ChainFileReader<R> {
+    reader: R,
+    offset: u64,
+    max_block_size: usize,
+    failed: bool,
+}
+
+impl<R: Read> ChainFileReader<R> {
+    /// Creates a reader with the [`DEFAULT_MAX_BLOCK_SIZE`].
+    pub const fn new(reader: R) -> Self {
+        Self {
+            reader,
+            offset: 0,
+            max_block_size: DEFAULT_MAX_BLOCK_SIZE,
+            failed: false,
+        }
+    }
+
+    /// Sets the limit on the encoded size of a single block.
+    pub const fn with_max_block_size(mut self, max_block_size: usize) -> Self {
+        self.max_block_size = max_block_size;
+        self
+    }
+
+    /// Returns the offset of the next block.
+    pub const fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Returns an iterator over the headers of the blocks.
+    pub fn headers(self) -> impl Iterator<Item = Result<GnosisHeader, ChainFileError>> {
+        self.map(|block| block.map(|block| block.header))
+    }
+
+    /// Reads the next block, or returns `None` at the end of the file.
+    pub fn read_block(&mut self) -> Result<Option<GnosisBlock>, ChainFileError> {
+        let offset = self.offset;
+        let decode_error = |error| ChainFileError::Decode { offset, error };
+
+        let mut first = [0];
+        if self.read_fully(&mut first)? == 0 {
+            return Ok(None);
+        }
+        // The first byte holds either the payload length or the number of length bytes following
+        let (length_bytes, short_length) = match first[0] {
+            0xf8.. => ((first[0] - 0xf7) as usize, 0),
+            0xc0.. => (0, (first[0] - 0xc0) as usize),
+            _ => return Err(decode_error(alloy_rlp::Error::UnexpectedString)),
+        };
+        let mut encoded = vec![first[0]];
+        encoded.resize(1 + length_bytes, 0);
+        self.read_exact_at(offset, &mut encoded[1..])?;
+
+        let payload_length = if length_bytes == 0 {
+            short_length
+        } else {
+            if encoded[1] == 0 {
+                return Err(decode_error(alloy_rlp::Error::LeadingZero));
+            }
+            let length = encoded[1..]
+                .iter()
+                .try_fold(0usize, |length, byte| {
+                    length
+                        .checked_mul(256)
+                        .map(|length| length + *byte as usize)
+                })
+                .ok_or_else(|| decode_error(alloy_rlp::Error::Overflow))?;
+            if length < 56 {
+                return Err(decode_error(alloy_rlp::Error::NonCanonicalSize));
+            }
+            length
+        };
+        let size = encoded.len().saturating_add(payload_length);
+        if size > self.max_block_size {
+            return Err(ChainFileError::BlockTooLarge {
+                offset,
+                size,
+                limit: self.max_block_size,
+            });
+        }
+        encoded.resize(size, 0);
+        self.read_exact_at(offset, &mut encoded[1 + length_bytes..])?;
+
+        alloy_rlp::decode_exact(&encoded)
+            .map(Some)
+            .map_err(decode_error)
+    }
+
+    /// Fills `buf` unless the file ends first, returning the number of bytes read.
+    fn read_fully(&mut self, buf: &mut [u8]) -> Result<usize, ChainFileError> {
+        let mut filled = 0;
+        while filled < buf.len() {
+            match self.reader.read(&mut buf[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(ChainFileError::Io(err)),
+            }
+        }
+        self.offset += filled as u64;
+        Ok(filled)
+    }
+
+    /// Fills `buf`, failing if the file ends within the block starting at `block_offset`.
+    fn read_exact_at(&mut self, block_offset: u64, buf: &mut [u8]) -> Result<(), ChainFileError> {
+        if self.read_fully(buf)? < buf.len() {
+            return Err(ChainFileError::Truncated(block_offset));
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for ChainFileReader<R> {
+    type Item = Result<GnosisBlock, ChainFileError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let block = self.read_block().transpose();
+        self.failed = matches!(block, Some(Err(_)));
+        block
+    }
+}
+
+/// Writes blocks to a chain file that reth and Nethermind can import.
+#[derive(Debug)]
+pub struct ChainFileWriter<W> {
+    writer: W,
+    buf: Vec<u8>,
+}
+
+impl<W: Write> ChainFileWriter<W> {
+    /// Creates a writer.
+    pub const fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Appends a block.
+    pub fn write_block(&mut self, block: &GnosisBlock) -> io::Result<()> {
+        self.buf.clear();
+        block.encode(&mut self.buf);
+        self.writer.write_all(&self.buf)
+    }
+
+    /// Flushes and returns the underlying writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestChainConfig, TestChainGenerator};
+
+    /// Blocks spanning AuRa, the merge, Shanghai, Cancun and Prague.
+    fn blocks() -> Vec<GnosisBlock> {
+        let mut generator = TestChainGenerator::new(TestChainConfig {
+            with_bodies: true,
+            ..Default::default()
+        });
+        generator
+            .blocks(18)
+            .into_iter()
+            .map(|block| GnosisBlock::new(block.header, block.body.unwrap()))
+            .collect()
+    }
+
+    fn chain_file(blocks: &[GnosisBlock]) -> Vec<u8> {
+        let mut writer = ChainFileWriter::new(Vec::new());
+        for block in blocks {
+            writer.write_block(block).unwrap();
+        }
+        writer.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let blocks = blocks();
+        let file = chain_file(&blocks);
+        assert_eq!(
+            file,
+            blocks
+                .iter()
+                .flat_map(alloy_rlp::encode)
+                .collect::<Vec<_>>()
+        );
+
+        let read = ChainFileReader::new(file.as_slice())
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(read, blocks);
+        assert!(read[0].header.is_pre_merge());
+        assert!(read[17].header.is_post_merge());
+
+        let headers = ChainFileReader::new(file.as_slice())
+            .headers()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(headers.len(), blocks.len());
+        assert!(ChainFileReader::new(&[][..]).next().is_none());
+    }
+
+    #[test]
+    fn test_reports_offsets() {
+        let blocks = blocks();
+        let file = chain_file(&blocks[..3]);
+        let second = alloy_rlp::encode(&blocks[0]).len() as u64;
+        let third = second + alloy_rlp::encode(&blocks[1]).len() as u64;
+
+        // Truncated in the middle of the third block
+        let mut reader = ChainFileReader::new(&file[..file.len() - 1]);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().unwrap().is_ok());
+        assert_eq!(reader.offset(), third);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(ChainFileError::Truncated(offset))) if offset == third
+        ));
+        assert!(reader.next().is_none());
+
+        // A string where the second block should start
+        let mut corrupted = file.clone();
+        corrupted[second as usize] = 0x80;
+        let mut reader = ChainFileReader::new(corrupted.as_slice());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(ChainFileError::Decode { offset, .. })) if offset == second
+        ));
+
+        let mut reader = ChainFileReader::new(file.as_slice()).with_max_block_size(16);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(ChainFileError::BlockTooLarge { offset: 0, .. }))
+        ));
+    }
+}
@@ -0,0 +1,667 @@
+use std::collections::BTreeMap;
+
+use alloy_consensus::EMPTY_OMMER_ROOT_HASH;
+use alloy_eips::{
+    eip7594::MAX_BLOBS_PER_TX_FUSAKA, eip7685::EMPTY_REQUESTS_HASH, eip7840, eip7840::BlobParams,
+};
+use alloy_genesis::{ChainConfig, GenesisAccount};
+use alloy_primitives::{Address, B64, B256, BlockNumber, Bytes, FixedBytes, U256};
+use alloy_trie::EMPTY_ROOT_HASH;
+use serde::{Deserialize, de::IgnoredAny};
+
+use crate::{
+    blob_schedule::{BlobSchedule, GNOSIS_BLOB_PARAMS},
+    block_rewards::BlockRewardContracts,
+    fee_collector::FeeCollector,
+    fork_id::{ForkFilterKey, ForkSchedule},
+    header::GnosisHeader,
+    step_schedule::{StepSchedule, StepScheduleError},
+    validator_set::ValidatorSetConfig,
+};
+
+/// Base fee of the first London block when the chain spec does not set one, 1 gwei.
+const DEFAULT_INITIAL_BASE_FEE: u64 = 1_000_000_000;
+
+/// A chain spec in the OpenEthereum and Nethermind JSON format, with an `authorityRound` engine.
+///
+/// This is the format of the canonical Gnosis Chain and Chiado specs. Hardfork transitions are
+/// mapped to their Ethereum names in [`AuRaChainSpec::chain_config`], following Nethermind:
+/// Byzantium at `eip140Transition`, Constantinople at `eip145Transition`, Petersburg at
+/// `eip1283DisableTransition`, Istanbul at `eip1344Transition`, Berlin at `eip2929Transition`,
+/// London at `eip1559Transition`, and Shanghai, Cancun, Prague and Osaka at the timestamps of
+/// EIP-3651, EIP-4844, EIP-7702 and EIP-7594.
+///
+/// The genesis state root is taken from `genesis.stateRoot` when present and computed from the
+/// `accounts` otherwise. Accounts that only declare a builtin are not part of the state, and
+/// accounts with a `constructor` are rejected since running it needs an EVM.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "ChainSpecJson")]
+pub struct AuRaChainSpec {
+    /// Name of the chain.
+    pub name: String,
+    /// Chain id and Ethereum hardfork activations.
+    pub chain_config: ChainConfig,
+    /// AuRa step durations in seconds, keyed by the timestamp from which they apply.
+    pub step_durations: BTreeMap<u64, u64>,
+    /// Validator set transitions.
+    pub validators: ValidatorSetConfig,
+    /// Block reward contract transitions.
+    pub block_reward_contracts: BlockRewardContracts,
+    /// Block from which POSDAO is active, if any.
+    pub posdao_transition: Option<BlockNumber>,
+    /// Block from which finality requires two-thirds of the validators, if any.
+    pub two_thirds_majority_transition: Option<BlockNumber>,
+    /// Contract processing withdrawals, if any.
+    pub withdrawal_contract: Option<Address>,
+    /// Contract emitting deposit events, if any.
+    pub deposit_contract: Option<Address>,
+    /// Where burnt fees go, if they are collected.
+    pub fee_collector: Option<FeeCollector>,
+    /// Blob parameters from Cancun onward.
+    pub blob_schedule: BlobSchedule,
+    /// The genesis header.
+    pub genesis: GnosisHeader,
+    /// The genesis allocations.
+    pub accounts: BTreeMap<Address, GenesisAccount>,
+}
+
+impl AuRaChainSpec {
+    /// Returns the chain id.
+    pub const fn chain_id(&self) -> u64 {
+        self.chain_config.chain_id
+    }
+
+    /// Returns the AuRa step schedule built from the step durations.
+    pub fn step_schedule(&self) -> Result<StepSchedule, StepScheduleError> {
+        StepSchedule::new(self.step_durations.clone())
+    }
+
+    /// Returns the EIP-2124 fork schedule of the chain, including the POSDAO transition.
+    pub fn fork_schedule(&self) -> ForkSchedule {
+        let mut schedule = ForkSchedule::from_chain_config(&self.chain_config, &self.genesis);
+        schedule
+            .forks
+            .extend(self.posdao_transition.map(ForkFilterKey::Block));
+        schedule
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ChainSpecJson {
+    name: String,
+    engine: EngineJson,
+    params: ParamsJson,
+    genesis: GenesisJson,
+    #[serde(default)]
+    accounts: BTreeMap<Address, AccountJson>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EngineJson {
+    authority_round: AuthorityRoundJson,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuthorityRoundJson {
+    params: AuRaParamsJson,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AuRaParamsJson {
+    step_duration: StepDurationJson,
+    validators: ValidatorSetConfig,
+    block_reward_contract_address: Option<Address>,
+    block_reward_contract_transition: Option<U256>,
+    #[serde(default)]
+    block_reward_contract_transitions: BTreeMap<String, Address>,
+    posdao_transition: Option<U256>,
+    two_thirds_majority_transition: Option<U256>,
+    withdrawal_contract_address: Option<Address>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StepDurationJson {
+    Fixed(U256),
+    Transitions(BTreeMap<String, U256>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ParamsJson {
+    #[serde(rename = "networkID")]
+    network_id: U256,
+    #[serde(rename = "chainID")]
+    chain_id: Option<U256>,
+    eip150_transition: Option<U256>,
+    eip155_transition: Option<U256>,
+    eip161abc_transition: Option<U256>,
+    eip140_transition: Option<U256>,
+    eip145_transition: Option<U256>,
+    eip1283_disable_transition: Option<U256>,
+    eip1344_transition: Option<U256>,
+    eip2929_transition: Option<U256>,
+    eip1559_transition: Option<U256>,
+    eip3651_transition_timestamp: Option<U256>,
+    eip4844_transition_timestamp: Option<U256>,
+    eip7702_transition_timestamp: Option<U256>,
+    eip7594_transition_timestamp: Option<U256>,
+    terminal_total_difficulty: Option<U256>,
+    eip1559_base_fee_initial_value: Option<U256>,
+    eip1559_fee_collector: Option<Address>,
+    eip1559_fee_collector_transition: Option<U256>,
+    eip4844_fee_collector_transition_timestamp: Option<U256>,
+    deposit_contract_address: Option<Address>,
+    #[serde(default)]
+    blob_schedule: Vec<BlobScheduleEntryJson>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlobScheduleEntryJson {
+    timestamp: U256,
+    target: U256,
+    max: U256,
+    base_fee_update_fraction: U256,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenesisJson {
+    seal: SealJson,
+    difficulty: U256,
+    gas_limit: U256,
+    author: Option<Address>,
+    timestamp: Option<U256>,
+    parent_hash: Option<B256>,
+    extra_data: Option<Bytes>,
+    state_root: Option<B256>,
+    base_fee_per_gas: Option<U256>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum SealJson {
+    AuthorityRound {
+        step: U256,
+        signature: FixedBytes<65>,
+    },
+    Ethereum {
+        nonce: B64,
+        #[serde(rename = "mixHash")]
+        mix_hash: B256,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountJson {
+    balance: Option<U256>,
+    nonce: Option<U256>,
+    code: Option<Bytes>,
+    storage: Option<BTreeMap<U256, U256>>,
+    constructor: Option<IgnoredAny>,
+}
+
+/// Converts a quantity to a `u64`, naming the field on overflow.
+fn to_u64(value: U256, field: &str) -> Result<u64, String> {
+    u64::try_from(value).map_err(|_| format!("{field} does not fit in 64 bits"))
+}
+
+fn opt_u64(value: Option<U256>, field: &str) -> Result<Option<u64>, String> {
+    value.map(|value| to_u64(value, field)).transpose()
+}
+
+/// Parses a map key holding a decimal or `0x` prefixed hex number.
+fn parse_key(key: &str) -> Result<u64, String> {
+    match key.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => key.parse(),
+    }
+    .map_err(|err| format!("invalid transition {key:?}: {err}"))
+}
+
+impl TryFrom<ChainSpecJson> for AuRaChainSpec {
+    type Error = String;
+
+    fn try_from(json: ChainSpecJson) -> Result<Self, Self::Error> {
+        let ChainSpecJson {
+            name,
+            engine,
+            params,
+            genesis,
+            accounts,
+        } = json;
+        let aura = engine.authority_round.params;
+
+        let chain_config = ChainConfig {
+            chain_id: to_u64(params.chain_id.unwrap_or(params.network_id), "chainID")?,
+            homestead_block: Some(0),
+            eip150_block: opt_u64(params.eip150_transition, "eip150Transition")?,
+            eip155_block: opt_u64(params.eip155_transition, "eip155Transition")?,
+            eip158_block: opt_u64(params.eip161abc_transition, "eip161abcTransition")?,
+            byzantium_block: opt_u64(params.eip140_transition, "eip140Transition")?,
+            constantinople_block: opt_u64(params.eip145_transition, "eip145Transition")?,
+            petersburg_block: opt_u64(
+                params.eip1283_disable_transition,
+                "eip1283DisableTransition",
+            )?,
+            istanbul_block: opt_u64(params.eip1344_transition, "eip1344Transition")?,
+            berlin_block: opt_u64(params.eip2929_transition, "eip2929Transition")?,
+            london_block: opt_u64(params.eip1559_transition, "eip1559Transition")?,
+            shanghai_time: opt_u64(
+                params.eip3651_transition_timestamp,
+                "eip3651TransitionTimestamp",
+            )?,
+            cancun_time: opt_u64(
+                params.eip4844_transition_timestamp,
+                "eip4844TransitionTimestamp",
+            )?,
+            prague_time: opt_u64(
+                params.eip7702_transition_timestamp,
+                "eip7702TransitionTimestamp",
+            )?,
+            osaka_time: opt_u64(
+                params.eip7594_transition_timestamp,
+                "eip7594TransitionTimestamp",
+            )?,
+            terminal_total_difficulty: params.terminal_total_difficulty,
+            ..Default::default()
+        };
+
+        let step_durations = match aura.step_duration {
+            StepDurationJson::Fixed(duration) => {
+                BTreeMap::from([(0, to_u64(duration, "stepDuration")?)])
+            }
+            StepDurationJson::Transitions(transitions) => transitions
+                .into_iter()
+                .map(|(key, duration)| Ok((parse_key(&key)?, to_u64(duration, "stepDuration")?)))
+                .collect::<Result<_, String>>()?,
+        };
+        StepSchedule::new(step_durations.clone()).map_err(|err| err.to_string())?;
+
+        let mut block_reward_contracts = BlockRewardContracts::default();
+        if let Some(contract) = aura.block_reward_contract_address {
+            let transition = opt_u64(
+                aura.block_reward_contract_transition,
+                "blockRewardContractTransition",
+            )?;
+            block_reward_contracts.insert(transition.unwrap_or_default(), contract);
+        }
+        for (key, contract) in aura.block_reward_contract_transitions {
+            block_reward_contracts.insert(parse_key(&key)?, contract);
+        }
+
+        let fee_collector = params
+            .eip1559_fee_collector
+            .map(|address| {
+                Ok::<_, String>(FeeCollector {
+                    address,
+                    base_fee_transition: opt_u64(
+                        params.eip1559_fee_collector_transition,
+                        "eip1559FeeCollectorTransition",
+                    )?
+                    .or(chain_config.london_block)
+                    .unwrap_or_default(),
+                    blob_fee_transition: opt_u64(
+                        params.eip4844_fee_collector_transition_timestamp,
+                        "eip4844FeeCollectorTransitionTimestamp",
+                    )?,
+                })
+            })
+            .transpose()?;
+
+        let mut blob_schedule = BlobSchedule::default();
+        for entry in params.blob_schedule {
+            let timestamp = to_u64(entry.timestamp, "blobSchedule timestamp")?;
+            let max = to_u64(entry.max, "blobSchedule max")?;
+            let params = BlobParams {
+                target_blob_count: to_u64(entry.target, "blobSchedule target")?,
+                max_blob_count: max,
+                update_fraction: entry
+                    .base_fee_update_fraction
+                    .try_into()
+                    .map_err(|_| "blobSchedule baseFeeUpdateFraction does not fit in 128 bits")?,
+                min_blob_fee: GNOSIS_BLOB_PARAMS.min_blob_fee,
+                max_blobs_per_tx: max,
+                blob_base_cost: 0,
+            };
+            let params = if chain_config
+                .osaka_time
+                .is_some_and(|osaka| timestamp >= osaka)
+            {
+                params
+                    .with_blob_base_cost(eip7840::BLOB_BASE_COST)
+                    .with_max_blobs_per_tx(MAX_BLOBS_PER_TX_FUSAKA.min(max))
+            } else {
+                params
+            };
+            blob_schedule.insert(timestamp, params);
+        }
+        // Without a schedule, Cancun starts with the Gnosis defaults
+        if let Some(cancun) = chain_config.cancun_time
+            && blob_schedule.params_at_timestamp(cancun).is_none()
+        {
+            blob_schedule.insert(cancun, GNOSIS_BLOB_PARAMS);
+        }
+
+        let mut allocations = BTreeMap::new();
+        for (address, account) in accounts {
+            if account.constructor.is_some() {
+                return Err(format!("account {address} has a constructor"));
+            }
+            if account.balance.is_none()
+                && account.nonce.is_none()
+                && account.code.is_none()
+                && account.storage.is_none()
+            {
+                continue;
+            }
+            let genesis_account = GenesisAccount {
+                nonce: opt_u64(account.nonce, "nonce")?,
+                balance: account.balance.unwrap_or_default(),
+                code: account.code,
+                storage: account.storage.map(|storage| {
+                    storage
+                        .into_iter()
+                        .map(|(slot, value)| (B256::from(slot), B256::from(value)))
+                        .collect()
+                }),
+                private_key: None,
+            };
+            allocations.insert(address, genesis_account);
+        }
+
+        let timestamp = opt_u64(genesis.timestamp, "genesis timestamp")?.unwrap_or_default();
+        let active_at_genesis = |fork: Option<u64>| fork.is_some_and(|time| time <= timestamp);
+        let (aura_step, aura_seal, mix_hash, nonce) = match genesis.seal {
+            SealJson::AuthorityRound { step, signature } => {
+                (Some(step), Some(signature), None, None)
+            }
+            SealJson::Ethereum { nonce, mix_hash } => (None, None, Some(mix_hash), Some(nonce)),
+        };
+        let london_at_genesis = chain_config.london_block == Some(0);
+        let cancun_at_genesis = active_at_genesis(chain_config.cancun_time);
+        let genesis = GnosisHeader {
+            parent_hash: genesis.parent_hash.unwrap_or_default(),
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            beneficiary: genesis.author.unwrap_or_default(),
+            state_root: genesis
+                .state_root
+                .unwrap_or_else(|| alloy_trie::root::state_root_ref_unhashed(&allocations)),
+            transactions_root: EMPTY_ROOT_HASH,
+            receipts_root: EMPTY_ROOT_HASH,
+            difficulty: genesis.difficulty,
+            number: 0,
+            gas_limit: to_u64(genesis.gas_limit, "genesis gasLimit")?,
+            timestamp,
+            extra_data: genesis.extra_data.unwrap_or_default(),
+            mix_hash,
+            nonce,
+            aura_step,
+            aura_seal,
+            base_fee_per_gas: london_at_genesis
+                .then(|| {
+                    opt_u64(
+                        genesis
+                            .base_fee_per_gas
+                            .or(params.eip1559_base_fee_initial_value),
+                        "genesis baseFeePerGas",
+                    )
+                    .map(|base_fee| base_fee.unwrap_or(DEFAULT_INITIAL_BASE_FEE))
+                })
+                .transpose()?,
+            withdrawals_root: active_at_genesis(chain_config.shanghai_time)
+                .then_some(EMPTY_ROOT_HASH),
+            blob_gas_used: cancun_at_genesis.then_some(0),
+            excess_blob_gas: cancun_at_genesis.then_some(0),
+            parent_beacon_block_root: cancun_at_genesis.then_some(B256::ZERO),
+            requests_hash: active_at_genesis(chain_config.prague_time)
+                .then_some(EMPTY_REQUESTS_HASH),
+            ..Default::default()
+        };
+
+        Ok(Self {
+            name,
+            chain_config,
+            step_durations,
+            validators: aura.validators,
+            block_reward_contracts,
+            posdao_transition: opt_u64(aura.posdao_transition, "posdaoTransition")?,
+            two_thirds_majority_transition: opt_u64(
+                aura.two_thirds_majority_transition,
+                "twoThirdsMajorityTransition",
+            )?,
+            withdrawal_contract: aura.withdrawal_contract_address,
+            deposit_contract: params.deposit_contract_address,
+            fee_collector,
+            blob_schedule,
+            genesis,
+            accounts: allocations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        fork_id::GNOSIS_GENESIS_HASH, validator_set::ValidatorSource,
+        withdrawals::GNOSIS_DEPOSIT_CONTRACT_ADDRESS,
+    };
+    use alloy_primitives::address;
+
+    /// The parts of the Gnosis Chain spec that this parser reads.
+    const GNOSIS_SPEC: &str = r#"{
+        "name": "GnosisChain",
+        "engine": {
+            "authorityRound": {
+                "params": {
+                    "stepDuration": 5,
+                    "blockReward": "0x0",
+                    "maximumUncleCountTransition": 0,
+                    "maximumUncleCount": 0,
+                    "validators": {
+                        "multi": {
+                            "0": {"list": ["0xcace5b3c29211740e595850e80478416ee77ca21"]},
+                            "1300": {"safeContract": "0x22e1229a2c5b95a60983b5577f745a603284f535"},
+                            "9186425": {"contract": "0xB87BE9f7196F2AE084Ca1DE6af5264292976e013"}
+                        }
+                    },
+                    "blockRewardContractAddress": "0x867305d19606aadba405ce534e303d0e225f9556",
+                    "blockRewardContractTransition": 1310,
+                    "blockRewardContractTransitions": {
+                        "9186425": "0x481c034c6d9441db23ea48de68bcae812c5d39ba"
+                    },
+                    "posdaoTransition": 9186425,
+                    "withdrawalContractAddress": "0x0B98057eA310F4d31F2a452B414647007d1645d9"
+                }
+            }
+        },
+        "params": {
+            "gasLimitBoundDivisor": "0x400",
+            "maximumExtraDataSize": "0x20",
+            "minGasLimit": "0x1388",
+            "networkID": "100",
+            "eip140Transition": "0x0",
+            "eip211Transition": "0x0",
+            "eip214Transition": "0x0",
+            "eip658Transition": "0x0",
+            "eip145Transition": 1604400,
+            "eip1014Transition": 1604400,
+            "eip1052Transition": 1604400,
+            "eip1283Transition": 1604400,
+            "eip1283DisableTransition": 2508800,
+            "eip1344Transition": 7298030,
+            "eip2929Transition": 16101500,
+            "eip2930Transition": 16101500,
+            "eip1559Transition": 19040000,
+            "eip1559FeeCollector": "0x6BBe78ee9e474842Dbd4AB4987b3CeFE88426A92",
+            "eip1559FeeCollectorTransition": 19040000,
+            "eip3651TransitionTimestamp": "0x64c8edbc",
+            "eip4844TransitionTimestamp": "0x65ef4dbc",
+            "eip7702TransitionTimestamp": "0x68122dbc",
+            "eip4844FeeCollectorTransitionTimestamp": "0x68122dbc",
+            "depositContractAddress": "0x0B98057eA310F4d31F2a452B414647007d1645d9",
+            "blobSchedule": [
+                {"timestamp": "0x65ef4dbc", "target": 1, "max": 2, "baseFeeUpdateFraction": "0x10fafa"},
+                {"timestamp": "0x68122dbc", "target": 1, "max": 2, "baseFeeUpdateFraction": "0x10fafa"}
+            ]
+        },
+        "genesis": {
+            "seal": {
+                "authorityRound": {
+                    "step": "0x0",
+                    "signature": "0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000"
+                }
+            },
+            "difficulty": "0x20000",
+            "gasLimit": "0x989680",
+            "stateRoot": "0x40cf4430ecaa733787d1a65154a3b9efb560c95d9e324a23b97f0609b539133b"
+        },
+        "accounts": {
+            "0x0000000000000000000000000000000000000001": {
+                "builtin": {"name": "ecrecover", "pricing": {"linear": {"base": 3000, "word": 0}}}
+            }
+        }
+    }"#;
+
+    #[test]
+    fn test_gnosis_spec() {
+        let spec: AuRaChainSpec = serde_json::from_str(GNOSIS_SPEC).unwrap();
+        assert_eq!(spec.name, "GnosisChain");
+        assert_eq!(spec.chain_id(), 100);
+        assert_eq!(spec.genesis.hash_slow(), GNOSIS_GENESIS_HASH);
+        assert!(spec.genesis.is_pre_merge());
+        assert!(spec.accounts.is_empty());
+
+        assert_eq!(spec.step_durations, BTreeMap::from([(0, 5)]));
+        assert_eq!(spec.step_schedule(), Ok(StepSchedule::gnosis()));
+        assert_eq!(
+            spec.validators
+                .source_at(9_186_426)
+                .map(|(_, source)| source),
+            Some(&ValidatorSource::Contract(address!(
+                "b87be9f7196f2ae084ca1de6af5264292976e013"
+            )))
+        );
+        assert_eq!(spec.block_reward_contracts, BlockRewardContracts::gnosis());
+        assert_eq!(spec.fee_collector, Some(FeeCollector::gnosis()));
+        assert_eq!(spec.blob_schedule, BlobSchedule::gnosis());
+        assert_eq!(spec.deposit_contract, Some(GNOSIS_DEPOSIT_CONTRACT_ADDRESS));
+        assert_eq!(spec.chain_config.shanghai_time, Some(1_690_889_660));
+
+        // The transitions reproduce the fork identifiers of the built-in schedule
+        let (parsed, known) = (spec.fork_schedule(), ForkSchedule::gnosis());
+        for (number, timestamp) in [
+            (0, 0),
+            (9_186_425, 0),
+            (19_040_000, 0),
+            (30_000_000, 1_690_889_660),
+            (40_000_000, 1_746_021_820),
+        ] {
+            assert_eq!(
+                parsed.fork_id(number, timestamp),
+                known.fork_id(number, timestamp)
+            );
+        }
+    }
+
+    #[test]
+    fn test_devnet_spec() {
+        let spec: AuRaChainSpec = serde_json::from_str(
+            r#"{
+                "name": "devnet",
+                "engine": {
+                    "authorityRound": {
+                        "params": {
+                            "stepDuration": {"0": 5, "0x64": 3},
+                            "validators": {"list": ["0x00000000000000000000000000000000000000aa"]},
+                            "twoThirdsMajorityTransition": 10
+                        }
+                    }
+                },
+                "params": {
+                    "networkID": "0x539",
+                    "eip1559Transition": 0,
+                    "eip3651TransitionTimestamp": 0,
+                    "eip4844TransitionTimestamp": 0
+                },
+                "genesis": {
+                    "seal": {"ethereum": {"nonce": "0x0000000000000000", "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000"}},
+                    "difficulty": "0x0",
+                    "gasLimit": "0x1c9c380",
+                    "timestamp": "0x10"
+                },
+                "accounts": {
+                    "0x0000000000000000000000000000000000000001": {"builtin": {"name": "ecrecover"}},
+                    "0x00000000000000000000000000000000000000aa": {"balance": "1000000000000000000"},
+                    "0x00000000000000000000000000000000000000bb": {
+                        "balance": "0",
+                        "code": "0x6000",
+                        "storage": {"0x01": "0x02"}
+                    }
+                }
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(spec.chain_id(), 1337);
+        assert_eq!(spec.step_durations, BTreeMap::from([(0, 5), (100, 3)]));
+        assert_eq!(spec.step_schedule(), StepSchedule::new([(0, 5), (100, 3)]));
+        assert_eq!(spec.two_thirds_majority_transition, Some(10));
+        assert_eq!(spec.fork_schedule().fork_id(0, 16).next, 0);
+        assert_eq!(
+            spec.blob_schedule.params_at_timestamp(16),
+            Some(GNOSIS_BLOB_PARAMS)
+        );
+
+        assert_eq!(spec.accounts.len(), 2);
+        let contract = &spec.accounts[&address!("00000000000000000000000000000000000000bb")];
+        assert_eq!(
+            contract.storage.as_ref().unwrap()[&B256::with_last_byte(1)],
+            B256::with_last_byte(2)
+        );
+
+        let genesis = &spec.genesis;
+        assert!(genesis.is_post_merge());
+        assert_eq!(genesis.timestamp, 16);
+        assert_eq!(
+            genesis.state_root,
+            alloy_trie::root::state_root_ref_unhashed(&spec.accounts)
+        );
+        assert_eq!(genesis.base_fee_per_gas, Some(DEFAULT_INITIAL_BASE_FEE));
+        assert_eq!(genesis.withdrawals_root, Some(EMPTY_ROOT_HASH));
+        assert_eq!(genesis.excess_blob_gas, Some(0));
+        assert_eq!(genesis.requests_hash, None);
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        let spec = GNOSIS_SPEC.replace(
+            r#""builtin": {"name": "ecrecover", "pricing": {"linear": {"base": 3000, "word": 0}}}"#,
+            r#""constructor": "0x6000""#,
+        );
+        let err = serde_json::from_str::<AuRaChainSpec>(&spec).unwrap_err();
+        assert!(err.to_string().contains("has a constructor"));
+
+        let spec = GNOSIS_SPEC.replace(
+            r#""eip145Transition": 1604400"#,
+            r#""eip145Transition": "0x10000000000000000""#,
+        );
+        let err = serde_json::from_str::<AuRaChainSpec>(&spec).unwrap_err();
+        assert!(err.to_string().contains("eip145Transition"));
+
+        let spec = GNOSIS_SPEC.replace(
+            r#""stepDuration": 5"#,
+            r#""stepDuration": {"0": 5, "100": 0}"#,
+        );
+        let err = serde_json::from_str::<AuRaChainSpec>(&spec).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("zero step duration at timestamp 100")
+        );
+    }
+}
@@ -0,0 +1,667 @@
+use std::collections::BTreeMap;
+
+use alloy_consensus::EMPTY_OMMER_ROOT_HASH;
+use alloy_eips::{
+    eip2935::{HISTORY_STORAGE_ADDRESS, HISTORY_STORAGE_CODE},
+    eip4788::{BEACON_ROOTS_ADDRESS, BEACON_ROOTS_CODE},
+    eip7002::{WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_PREDEPLOY_CODE},
+    eip7251::{CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS, CONSOLIDATION_REQUEST_PREDEPLOY_CODE},
+    eip7685::EMPTY_REQUESTS_HASH,
+};
+use alloy_genesis::{ChainConfig, Genesis, GenesisAccount};
+use alloy_primitives::{Address, B64, B256, Bytes, FixedBytes, U256, address, bytes};
+use alloy_trie::EMPTY_ROOT_HASH;
+use serde_json::{Value, json};
+
+use crate::{
+    blob_schedule::GNOSIS_BLOB_PARAMS, fee_collector::CHIADO_FEE_COLLECTOR, header::GnosisHeader,
+};
+
+/// Default chain id of a devnet.
+pub const DEVNET_CHAIN_ID: u64 = 1337;
+
+/// Address of the deposit contract on a devnet.
+pub const DEVNET_DEPOSIT_CONTRACT_ADDRESS: Address =
+    address!("4242424242424242424242424242424242424242");
+
+/// Address of the block reward contract on a devnet, the same as on Chiado.
+pub const DEVNET_BLOCK_REWARD_CONTRACT_ADDRESS: Address =
+    address!("2000000000000000000000000000000000000001");
+
+/// Address of the fee collector on a devnet, the same as on Chiado.
+pub const DEVNET_FEE_COLLECTOR: Address = CHIADO_FEE_COLLECTOR;
+
+/// Runtime code of a block reward contract that mints nothing.
+///
+/// Whatever the input, it returns two empty arrays, the ABI encoding of an empty
+/// `(address[] receiversNative, uint256[] rewardsNative)`.
+pub const NOOP_BLOCK_REWARD_CODE: Bytes = bytes!("6040600052606060205260806000f3");
+
+/// Base fee of the genesis block, 1 gwei.
+const GENESIS_BASE_FEE: u64 = 1_000_000_000;
+
+/// Difficulty of AuRa blocks sealed in turn.
+const AURA_DIFFICULTY: U256 = U256::from_limbs([0x20000, 0, 0, 0]);
+
+/// The latest hardfork active at the genesis of a devnet.
+///
+/// Every earlier hardfork is active from genesis as well, later ones are not scheduled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DevnetFork {
+    /// London, the first fork with a fee collector.
+    London,
+    /// Shanghai, adding withdrawals.
+    Shanghai,
+    /// Cancun, adding blobs and the beacon roots contract.
+    Cancun,
+    /// Prague, adding execution requests and the history storage contract.
+    Prague,
+}
+
+/// Errors returned when a [`DevnetConfig`] cannot be turned into a genesis.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum DevnetError {
+    /// Withdrawals come from the beacon chain, so Shanghai and later need a merged chain.
+    #[display("{_0:?} at genesis requires starting post-merge")]
+    ForkRequiresMerge(#[error(not(source))] DevnetFork),
+    /// An AuRa chain needs at least one validator to seal blocks.
+    #[display("a pre-merge devnet needs at least one validator")]
+    NoValidators,
+    /// The deposit contract has no code, so deposits and withdrawals would silently do nothing.
+    #[display("the deposit contract has no code")]
+    MissingDepositContract,
+}
+
+/// Parameters of a local Gnosis devnet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevnetConfig {
+    /// Chain and network id.
+    pub chain_id: u64,
+    /// The latest hardfork active at genesis.
+    pub fork: DevnetFork,
+    /// Whether the chain starts post-merge, driven by a beacon node from genesis.
+    pub merged: bool,
+    /// AuRa validators, sealing blocks until the merge.
+    pub validators: Vec<Address>,
+    /// AuRa step duration in seconds.
+    pub step_duration: u64,
+    /// Timestamp of the genesis block.
+    pub genesis_timestamp: u64,
+    /// Gas limit of the genesis block.
+    pub gas_limit: u64,
+    /// The deposit contract, placed at [`DEVNET_DEPOSIT_CONTRACT_ADDRESS`].
+    ///
+    /// This crate does not ship the deposit contract bytecode, so this must hold the runtime code
+    /// and storage of the compiled contract. It has no code by default, which
+    /// [`DevnetGenesis::new`] rejects.
+    pub deposit_contract: GenesisAccount,
+    /// Additional accounts, such as prefunded test accounts. They take precedence over the
+    /// predeployed contracts.
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+}
+
+impl Default for DevnetConfig {
+    fn default() -> Self {
+        Self {
+            chain_id: DEVNET_CHAIN_ID,
+            fork: DevnetFork::Prague,
+            merged: true,
+            validators: Vec::new(),
+            step_duration: 5,
+            genesis_timestamp: 0,
+            gas_limit: 30_000_000,
+            deposit_contract: GenesisAccount::default(),
+            alloc: BTreeMap::new(),
+        }
+    }
+}
+
+/// Genesis of a local Gnosis devnet, generated from a [`DevnetConfig`].
+///
+/// Besides the configured accounts, the allocations hold the deposit contract, a block reward
+/// contract that mints nothing, the fee collector, and the EIP-4788, EIP-2935, EIP-7002 and
+/// EIP-7251 system contracts. The system contracts are predeployed whatever the fork, so later
+/// forks can be scheduled by editing the generated files.
+///
+/// The genesis can be written out for reth with [`DevnetGenesis::to_genesis`], and for
+/// Nethermind with [`DevnetGenesis::to_chainspec_json`]. Both describe the same genesis block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevnetGenesis {
+    /// The configuration the genesis was generated from.
+    pub config: DevnetConfig,
+    /// Chain id and hardfork activations.
+    pub chain_config: ChainConfig,
+    /// The genesis allocations.
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+    /// The genesis header.
+    pub header: GnosisHeader,
+}
+
+impl DevnetGenesis {
+    /// Generates the genesis of a devnet.
+    pub fn new(config: DevnetConfig) -> Result<Self, DevnetError> {
+        if !config.merged && config.fork >= DevnetFork::Shanghai {
+            return Err(DevnetError::ForkRequiresMerge(config.fork));
+        }
+        if !config.merged && config.validators.is_empty() {
+            return Err(DevnetError::NoValidators);
+        }
+        if config
+            .deposit_contract
+            .code
+            .as_ref()
+            .is_none_or(|code| code.is_empty())
+        {
+            return Err(DevnetError::MissingDepositContract);
+        }
+
+        let active = |fork| (config.fork >= fork).then_some(0);
+        let mut blob_schedule = BTreeMap::new();
+        if config.fork >= DevnetFork::Cancun {
+            blob_schedule.insert("cancun".to_string(), GNOSIS_BLOB_PARAMS);
+        }
+        if config.fork >= DevnetFork::Prague {
+            blob_schedule.insert("prague".to_string(), GNOSIS_BLOB_PARAMS);
+        }
+        let chain_config = ChainConfig {
+            chain_id: config.chain_id,
+            homestead_block: Some(0),
+            eip150_block: Some(0),
+            eip155_block: Some(0),
+            eip158_block: Some(0),
+            byzantium_block: Some(0),
+            constantinople_block: Some(0),
+            petersburg_block: Some(0),
+            istanbul_block: Some(0),
+            berlin_block: Some(0),
+            london_block: Some(0),
+            shanghai_time: active(DevnetFork::Shanghai),
+            cancun_time: active(DevnetFork::Cancun),
+            prague_time: active(DevnetFork::Prague),
+            terminal_total_difficulty: config.merged.then_some(U256::ZERO),
+            terminal_total_difficulty_passed: config.merged,
+            blob_schedule,
+            ..Default::default()
+        };
+
+        let system_contract = |code: &Bytes| GenesisAccount {
+            nonce: Some(1),
+            code: Some(code.clone()),
+            ..Default::default()
+        };
+        let mut alloc = BTreeMap::from([
+            (
+                DEVNET_DEPOSIT_CONTRACT_ADDRESS,
+                config.deposit_contract.clone(),
+            ),
+            (
+                DEVNET_BLOCK_REWARD_CONTRACT_ADDRESS,
+                system_contract(&NOOP_BLOCK_REWARD_CODE),
+            ),
+            (DEVNET_FEE_COLLECTOR, GenesisAccount::default()),
+            (BEACON_ROOTS_ADDRESS, system_contract(&BEACON_ROOTS_CODE)),
+            (
+                HISTORY_STORAGE_ADDRESS,
+                system_contract(&HISTORY_STORAGE_CODE),
+            ),
+            (
+                WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+                system_contract(&WITHDRAWAL_REQUEST_PREDEPLOY_CODE),
+            ),
+            (
+                CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+                system_contract(&CONSOLIDATION_REQUEST_PREDEPLOY_CODE),
+            ),
+        ]);
+        alloc.extend(config.alloc.clone());
+
+        let (difficulty, mix_hash, nonce, aura_step, aura_seal) = if config.merged {
+            (U256::ZERO, Some(B256::ZERO), Some(B64::ZERO), None, None)
+        } else {
+            (
+                AURA_DIFFICULTY,
+                None,
+                None,
+                Some(U256::ZERO),
+                Some(FixedBytes::ZERO),
+            )
+        };
+        let cancun = config.fork >= DevnetFork::Cancun;
+        let header = GnosisHeader {
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            state_root: alloy_trie::root::state_root_ref_unhashed(&alloc),
+            transactions_root: EMPTY_ROOT_HASH,
+            receipts_root: EMPTY_ROOT_HASH,
+            difficulty,
+            gas_limit: config.gas_limit,
+            timestamp: config.genesis_timestamp,
+            mix_hash,
+            nonce,
+            aura_step,
+            aura_seal,
+            base_fee_per_gas: Some(GENESIS_BASE_FEE),
+            withdrawals_root: (config.fork >= DevnetFork::Shanghai).then_some(EMPTY_ROOT_HASH),
+            blob_gas_used: cancun.then_some(0),
+            excess_blob_gas: cancun.then_some(0),
+            parent_beacon_block_root: cancun.then_some(B256::ZERO),
+            requests_hash: (config.fork >= DevnetFork::Prague).then_some(EMPTY_REQUESTS_HASH),
+            ..Default::default()
+        };
+
+        Ok(Self {
+            config,
+            chain_config,
+            alloc,
+            header,
+        })
+    }
+
+    /// Returns the genesis in the format read by reth and geth.
+    ///
+    /// The format has no room for an AuRa seal, so a pre-merge genesis written this way has a
+    /// different hash than [`DevnetGenesis::header`].
+    pub fn to_genesis(&self) -> Genesis {
+        Genesis {
+            config: self.chain_config.clone(),
+            nonce: 0,
+            timestamp: self.header.timestamp,
+            extra_data: self.header.extra_data.clone(),
+            gas_limit: self.header.gas_limit,
+            difficulty: self.header.difficulty,
+            mix_hash: self.header.mix_hash.unwrap_or_default(),
+            coinbase: self.header.beneficiary,
+            alloc: self.alloc.clone(),
+            base_fee_per_gas: self.header.base_fee_per_gas.map(u128::from),
+            excess_blob_gas: self.header.excess_blob_gas,
+            blob_gas_used: self.header.blob_gas_used,
+            number: None,
+            parent_hash: None,
+        }
+    }
+
+    /// Returns the genesis as a Nethermind chain spec with an `authorityRound` engine.
+    ///
+    /// The spec can be read back with [`AuRaChainSpec`](crate::chainspec::AuRaChainSpec).
+    pub fn to_chainspec_json(&self) -> Value {
+        let config = &self.config;
+        let hex = |value: u64| format!("{value:#x}");
+        let timestamp = |fork| (config.fork >= fork).then(|| hex(0));
+
+        let seal = if config.merged {
+            json!({"ethereum": {"nonce": B64::ZERO, "mixHash": B256::ZERO}})
+        } else {
+            json!({"authorityRound": {"step": "0x0", "signature": FixedBytes::<65>::ZERO}})
+        };
+        let blob_schedule: Vec<_> = self
+            .chain_config
+            .blob_schedule
+            .iter()
+            .filter_map(|(fork, params)| {
+                let time = match fork.as_str() {
+                    "cancun" => self.chain_config.cancun_time,
+                    "prague" => self.chain_config.prague_time,
+                    _ => None,
+                }?;
+                Some(json!({
+                    "timestamp": hex(time),
+                    "target": params.target_blob_count,
+                    "max": params.max_blob_count,
+                    "baseFeeUpdateFraction": hex(params.update_fraction as u64),
+                }))
+            })
+            .collect();
+        let accounts: BTreeMap<_, _> = self
+            .alloc
+            .iter()
+            .map(|(address, account)| {
+                let mut json = json!({"balance": account.balance});
+                if let Some(nonce) = account.nonce {
+                    json["nonce"] = hex(nonce).into();
+                }
+                if let Some(code) = &account.code {
+                    json["code"] = json!(code);
+                }
+                if let Some(storage) = &account.storage {
+                    json["storage"] = json!(storage);
+                }
+                (address, json)
+            })
+            .collect();
+
+        let mut params = json!({
+            "gasLimitBoundDivisor": "0x400",
+            "maximumExtraDataSize": "0x20",
+            "minGasLimit": "0x1388",
+            "networkID": hex(config.chain_id),
+            "chainID": hex(config.chain_id),
+            "eip1559BaseFeeInitialValue": hex(GENESIS_BASE_FEE),
+            "eip1559FeeCollector": DEVNET_FEE_COLLECTOR,
+            "eip1559FeeCollectorTransition": 0,
+            "depositContractAddress": DEVNET_DEPOSIT_CONTRACT_ADDRESS,
+            "terminalTotalDifficulty": config.merged.then_some("0x0"),
+            "blobSchedule": blob_schedule,
+        });
+        // Nethermind enables nothing by default, so every block-activated EIP is listed
+        for eip in [
+            "eip150",
+            "eip160",
+            "eip161abc",
+            "eip161d",
+            "eip155",
+            "maxCodeSize",
+            "eip140",
+            "eip211",
+            "eip214",
+            "eip658",
+            "eip145",
+            "eip1014",
+            "eip1052",
+            "eip1283",
+            "eip1283Disable",
+            "eip152",
+            "eip1108",
+            "eip1344",
+            "eip1884",
+            "eip2028",
+            "eip2200",
+            "eip2565",
+            "eip2929",
+            "eip2930",
+            "eip1559",
+            "eip3198",
+            "eip3529",
+            "eip3541",
+        ] {
+            params[format!("{eip}Transition")] = hex(0).into();
+        }
+        params["maxCodeSize"] = hex(0x6000).into();
+        for (fork, eips) in [
+            (
+                DevnetFork::Shanghai,
+                &["eip3651", "eip3855", "eip3860", "eip4895"][..],
+            ),
+            (
+                DevnetFork::Cancun,
+                &["eip1153", "eip4788", "eip4844", "eip5656", "eip6780"],
+            ),
+            (
+                DevnetFork::Prague,
+                &[
+                    "eip2537",
+                    "eip2935",
+                    "eip6110",
+                    "eip7002",
+                    "eip7251",
+                    "eip7623",
+                    "eip7702",
+                    "eip4844FeeCollector",
+                ],
+            ),
+        ] {
+            if let Some(time) = timestamp(fork) {
+                for eip in eips {
+                    params[format!("{eip}TransitionTimestamp")] = time.clone().into();
+                }
+            }
+        }
+
+        json!({
+            "name": format!("gnosis-devnet-{}", config.chain_id),
+            "engine": {
+                "authorityRound": {
+                    "params": {
+                        "stepDuration": config.step_duration,
+                        "blockReward": "0x0",
+                        "maximumUncleCountTransition": 0,
+                        "maximumUncleCount": 0,
+                        "validators": {"list": config.validators},
+                        "blockRewardContractAddress": DEVNET_BLOCK_REWARD_CONTRACT_ADDRESS,
+                        "blockRewardContractTransition": 0,
+                        "withdrawalContractAddress": DEVNET_DEPOSIT_CONTRACT_ADDRESS,
+                    }
+                }
+            },
+            "params": params,
+            "genesis": {
+                "seal": seal,
+                "difficulty": self.header.difficulty,
+                "gasLimit": hex(self.header.gas_limit),
+                "timestamp": hex(self.header.timestamp),
+                "baseFeePerGas": self.header.base_fee_per_gas.map(hex),
+            },
+            "accounts": accounts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        block_rewards::{BlockRewardContracts, BlockRewards},
+        chainspec::AuRaChainSpec,
+        fee_collector::FeeCollector,
+        validator_set::{ValidatorSetConfig, ValidatorSource},
+    };
+    use alloy_consensus::Header;
+
+    /// A stand-in for the deposit contract, with some code and storage.
+    fn deposit_contract() -> GenesisAccount {
+        GenesisAccount::default()
+            .with_code(Some(bytes!("00")))
+            .with_storage(Some(BTreeMap::from([(
+                B256::with_last_byte(1),
+                B256::with_last_byte(2),
+            )])))
+    }
+
+    /// Builds the genesis header of a reth genesis file the way reth's `make_genesis_header`
+    /// does, with the forks active at the genesis timestamp.
+    fn reth_genesis_header(genesis: &Genesis) -> GnosisHeader {
+        let active = |time: Option<u64>| time.is_some_and(|time| time <= genesis.timestamp);
+        let cancun = active(genesis.config.cancun_time);
+        Header {
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            state_root: alloy_trie::root::state_root_ref_unhashed(&genesis.alloc),
+            transactions_root: EMPTY_ROOT_HASH,
+            receipts_root: EMPTY_ROOT_HASH,
+            difficulty: genesis.difficulty,
+            gas_limit: genesis.gas_limit,
+            timestamp: genesis.timestamp,
+            extra_data: genesis.extra_data.clone(),
+            mix_hash: genesis.mix_hash,
+            nonce: genesis.nonce.into(),
+            beneficiary: genesis.coinbase,
+            base_fee_per_gas: genesis.base_fee_per_gas.map(|fee| fee as u64),
+            withdrawals_root: active(genesis.config.shanghai_time).then_some(EMPTY_ROOT_HASH),
+            parent_beacon_block_root: cancun.then_some(B256::ZERO),
+            blob_gas_used: cancun.then(|| genesis.blob_gas_used.unwrap_or_default()),
+            excess_blob_gas: cancun.then(|| genesis.excess_blob_gas.unwrap_or_default()),
+            requests_hash: active(genesis.config.prague_time).then_some(EMPTY_REQUESTS_HASH),
+            ..Default::default()
+        }
+        .into()
+    }
+
+    fn prefunded() -> BTreeMap<Address, GenesisAccount> {
+        BTreeMap::from([(
+            Address::with_last_byte(0xaa),
+            GenesisAccount::default().with_balance(U256::from(10).pow(U256::from(24))),
+        )])
+    }
+
+    #[test]
+    fn test_prague_devnet() {
+        let devnet = DevnetGenesis::new(DevnetConfig {
+            deposit_contract: deposit_contract(),
+            alloc: prefunded(),
+            genesis_timestamp: 1_700_000_000,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let header = &devnet.header;
+        assert!(header.is_post_merge());
+        assert_eq!(header.requests_hash, Some(EMPTY_REQUESTS_HASH));
+        assert_eq!(header.parent_beacon_block_root, Some(B256::ZERO));
+        assert_eq!(
+            header.state_root,
+            alloy_trie::root::state_root_ref_unhashed(&devnet.alloc)
+        );
+        assert_eq!(devnet.alloc.len(), 8);
+        assert_eq!(
+            devnet.alloc[&HISTORY_STORAGE_ADDRESS].code,
+            Some(HISTORY_STORAGE_CODE.clone())
+        );
+
+        // Nethermind chain spec
+        let spec: AuRaChainSpec = serde_json::from_value(devnet.to_chainspec_json()).unwrap();
+        assert_eq!(spec.genesis, devnet.header);
+        assert_eq!(spec.chain_id(), DEVNET_CHAIN_ID);
+        assert_eq!(spec.chain_config.prague_time, Some(0));
+        assert_eq!(
+            spec.chain_config.terminal_total_difficulty,
+            Some(U256::ZERO)
+        );
+        assert_eq!(spec.accounts, devnet.alloc);
+        assert_eq!(
+            spec.block_reward_contracts,
+            BlockRewardContracts::new([(0, DEVNET_BLOCK_REWARD_CONTRACT_ADDRESS)])
+        );
+        assert_eq!(
+            spec.fee_collector,
+            Some(FeeCollector {
+                address: DEVNET_FEE_COLLECTOR,
+                base_fee_transition: 0,
+                blob_fee_transition: Some(0),
+            })
+        );
+        assert_eq!(
+            spec.blob_schedule.params_at_timestamp(0),
+            Some(GNOSIS_BLOB_PARAMS)
+        );
+
+        // reth genesis, which hashes to the same block
+        let json = serde_json::to_string(&devnet.to_genesis()).unwrap();
+        let genesis: Genesis = serde_json::from_str(&json).unwrap();
+        assert_eq!(genesis.alloc, devnet.alloc);
+        assert_eq!(genesis.config.prague_time, Some(0));
+        assert_eq!(genesis.timestamp, header.timestamp);
+        assert_eq!(genesis.base_fee_per_gas, Some(GENESIS_BASE_FEE as u128));
+        assert_eq!(genesis.excess_blob_gas, Some(0));
+        assert_eq!(
+            reth_genesis_header(&genesis).hash_slow(),
+            header.hash_slow()
+        );
+    }
+
+    #[test]
+    fn test_aura_devnet() {
+        let validators = vec![Address::with_last_byte(1), Address::with_last_byte(2)];
+        let devnet = DevnetGenesis::new(DevnetConfig {
+            fork: DevnetFork::London,
+            merged: false,
+            validators: validators.clone(),
+            deposit_contract: deposit_contract(),
+            alloc: prefunded(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let header = &devnet.header;
+        assert!(header.is_pre_merge());
+        assert_eq!(header.difficulty, AURA_DIFFICULTY);
+        assert_eq!(header.withdrawals_root, None);
+        assert_eq!(header.base_fee_per_gas, Some(GENESIS_BASE_FEE));
+        assert_eq!(devnet.chain_config.terminal_total_difficulty, None);
+        assert!(devnet.chain_config.blob_schedule.is_empty());
+
+        let spec: AuRaChainSpec = serde_json::from_value(devnet.to_chainspec_json()).unwrap();
+        assert_eq!(spec.genesis, devnet.header);
+        assert_eq!(spec.genesis.hash_slow(), devnet.header.hash_slow());
+        assert_eq!(spec.chain_config.shanghai_time, None);
+        assert_eq!(spec.step_durations, BTreeMap::from([(0, 5)]));
+        assert_eq!(
+            spec.validators,
+            ValidatorSetConfig::new([(0, ValidatorSource::List(validators))])
+        );
+    }
+
+    #[test]
+    fn test_invalid_configs() {
+        assert_eq!(
+            DevnetGenesis::new(DevnetConfig {
+                fork: DevnetFork::Shanghai,
+                merged: false,
+                validators: vec![Address::with_last_byte(1)],
+                ..Default::default()
+            }),
+            Err(DevnetError::ForkRequiresMerge(DevnetFork::Shanghai))
+        );
+        assert_eq!(
+            DevnetGenesis::new(DevnetConfig {
+                fork: DevnetFork::London,
+                merged: false,
+                ..Default::default()
+            }),
+            Err(DevnetError::NoValidators)
+        );
+        assert_eq!(
+            DevnetGenesis::new(DevnetConfig::default()),
+            Err(DevnetError::MissingDepositContract)
+        );
+        assert_eq!(
+            DevnetGenesis::new(DevnetConfig {
+                deposit_contract: GenesisAccount::default().with_code(Some(Bytes::new())),
+                ..Default::default()
+            }),
+            Err(DevnetError::MissingDepositContract)
+        );
+    }
+
+    /// Executes straight-line code made of `PUSH1`, `MSTORE` and `RETURN` only, and returns its
+    /// output.
+    fn execute(code: &[u8]) -> Vec<u8> {
+        let mut stack = Vec::new();
+        let mut memory = Vec::new();
+        let mut pc = 0;
+        loop {
+            match code[pc] {
+                // PUSH1
+                0x60 => {
+                    stack.push(code[pc + 1] as usize);
+                    pc += 2;
+                }
+                // MSTORE
+                0x52 => {
+                    let offset = stack.pop().expect("stack underflow");
+                    let value = stack.pop().expect("stack underflow");
+                    memory.resize(memory.len().max(offset + 32), 0);
+                    memory[offset..offset + 32]
+                        .copy_from_slice(&U256::from(value).to_be_bytes::<32>());
+                    pc += 1;
+                }
+                // RETURN
+                0xf3 => {
+                    let offset = stack.pop().expect("stack underflow");
+                    let size = stack.pop().expect("stack underflow");
+                    assert!(stack.is_empty());
+                    assert_eq!(pc + 1, code.len());
+                    memory.resize(memory.len().max(offset + size), 0);
+                    return memory[offset..offset + size].to_vec();
+                }
+                opcode => panic!("unexpected opcode {opcode:#04x} at {pc}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_noop_block_reward_output() {
+        let output = execute(&NOOP_BLOCK_REWARD_CODE);
+        let mut expected = [0; 128];
+        expected[31] = 0x40;
+        expected[63] = 0x60;
+        assert_eq!(output, expected);
+        assert_eq!(BlockRewards::decode(&output), Ok(BlockRewards::default()));
+    }
+}
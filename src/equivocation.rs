@@ -0,0 +1,231 @@
+use std::collections::BTreeMap;
+
+use alloy_primitives::{Address, B256, Bytes, U256};
+
+use crate::{
+    aura::{self, SealError},
+    header::GnosisHeader,
+    step_schedule::{StepError, StepSchedule},
+};
+
+/// Errors returned when a header cannot be checked for equivocation.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum EquivocationError {
+    /// The seal of the header is invalid.
+    #[display("{_0}")]
+    Seal(SealError),
+    /// The step of the header does not match its timestamp.
+    #[display("{_0}")]
+    Step(StepError),
+}
+
+/// Two distinct headers sealed by the same validator for the same AuRa step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equivocation {
+    /// The validator that sealed both headers.
+    pub signer: Address,
+    /// The step both headers were sealed for.
+    pub step: U256,
+    /// The header seen first.
+    pub first: GnosisHeader,
+    /// The conflicting header.
+    pub second: GnosisHeader,
+}
+
+impl Equivocation {
+    /// Returns the RLP encoding of both headers, the first seen header first.
+    ///
+    /// The step is not covered by the AuRa signature, so the seals alone do not prove that both
+    /// headers were sealed for [`Equivocation::step`]. Checking the evidence takes the chain's
+    /// [`StepSchedule`] as well: both seals must recover [`Equivocation::signer`], both timestamps
+    /// must be within the window of the step, and the seal hashes must differ.
+    pub fn evidence(&self) -> [Bytes; 2] {
+        [
+            alloy_rlp::encode(&self.first).into(),
+            alloy_rlp::encode(&self.second).into(),
+        ]
+    }
+}
+
+/// Detects validators sealing more than one header for the same AuRa step.
+///
+/// Headers can be fed from any number of sources and in any order. Headers are compared by
+/// [`GnosisHeader::aura_seal_hash`], the message the validator signed: copies of a header with a
+/// different step or seal are the same signed block, not an equivocation. Since the step is not
+/// signed, headers are only accepted if their timestamp is within the window of their step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EquivocationDetector {
+    /// The step durations of the chain.
+    schedule: StepSchedule,
+    /// Headers seen per signer and step, with their seal hash, in the order they were seen.
+    seen: BTreeMap<(Address, U256), Vec<(B256, GnosisHeader)>>,
+}
+
+impl EquivocationDetector {
+    /// Creates an empty detector for a chain with the given step durations.
+    pub fn new(schedule: StepSchedule) -> Self {
+        Self {
+            schedule,
+            seen: BTreeMap::new(),
+        }
+    }
+
+    /// Processes a pre-merge header.
+    ///
+    /// Returns the equivocation if the header conflicts with one seen before for the same
+    /// signer and step. The evidence always pairs the new header with the first header seen.
+    pub fn on_header(
+        &mut self,
+        header: &GnosisHeader,
+    ) -> Result<Option<Equivocation>, EquivocationError> {
+        let signer = aura::recover_seal_signer(header).map_err(EquivocationError::Seal)?;
+        let step = U256::from(
+            self.schedule
+                .verify_header(header)
+                .map_err(EquivocationError::Step)?,
+        );
+        let hash = header.aura_seal_hash();
+
+        let headers = self.seen.entry((signer, step)).or_default();
+        if headers.iter().any(|(seen, _)| *seen == hash) {
+            return Ok(None);
+        }
+        headers.push((hash, header.clone()));
+        if headers.len() == 1 {
+            return Ok(None);
+        }
+        Ok(Some(Equivocation {
+            signer,
+            step,
+            first: headers[0].1.clone(),
+            second: header.clone(),
+        }))
+    }
+
+    /// Forgets every header sealed for a step before `step`, to bound memory usage.
+    pub fn prune_before(&mut self, step: U256) {
+        self.seen.retain(|(_, seen_step), _| *seen_step >= step);
+    }
+
+    /// Returns the number of distinct headers currently held.
+    pub fn len(&self) -> usize {
+        self.seen.values().map(Vec::len).sum()
+    }
+
+    /// Returns `true` if no header is held.
+    pub fn is_empty(&self) -> bool {
+        self.seen.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        aura::{SigningKey, seal_header},
+        step_schedule::GNOSIS_STEP_DURATION,
+    };
+    use alloy_primitives::FixedBytes;
+    use alloy_rlp::Decodable;
+
+    fn sealed_header(step: u64, gas_used: u64, key: &SigningKey) -> GnosisHeader {
+        let header = GnosisHeader {
+            number: step,
+            gas_used,
+            timestamp: step * GNOSIS_STEP_DURATION,
+            ..Default::default()
+        };
+        seal_header(header, U256::from(step), key).unwrap()
+    }
+
+    #[test]
+    fn test_detects_double_signed_step() {
+        let key = SigningKey::from_slice(&[1; 32]).unwrap();
+        let mut detector = EquivocationDetector::new(StepSchedule::gnosis());
+
+        let first = sealed_header(10, 0, &key);
+        assert_eq!(detector.on_header(&first), Ok(None));
+        // The same header from another source is fine
+        assert_eq!(detector.on_header(&first), Ok(None));
+
+        let second = sealed_header(10, 21_000, &key);
+        let equivocation = detector.on_header(&second).unwrap().unwrap();
+        assert_eq!(equivocation.signer, Address::from_private_key(&key));
+        assert_eq!(equivocation.step, U256::from(10));
+
+        let [first_rlp, second_rlp] = equivocation.evidence();
+        assert_eq!(
+            GnosisHeader::decode(&mut first_rlp.as_ref()).unwrap(),
+            first
+        );
+        assert_eq!(
+            GnosisHeader::decode(&mut second_rlp.as_ref()).unwrap(),
+            second
+        );
+
+        // A third header is reported against the first one, the second is not reported again
+        let third = sealed_header(10, 42_000, &key);
+        assert_eq!(detector.on_header(&third).unwrap().unwrap().first, first);
+        assert_eq!(detector.on_header(&second), Ok(None));
+        assert_eq!(detector.len(), 3);
+    }
+
+    #[test]
+    fn test_ignores_other_steps_and_signers() {
+        let (a, b) = (
+            SigningKey::from_slice(&[1; 32]).unwrap(),
+            SigningKey::from_slice(&[2; 32]).unwrap(),
+        );
+        let mut detector = EquivocationDetector::new(StepSchedule::gnosis());
+        assert_eq!(detector.on_header(&sealed_header(10, 0, &a)), Ok(None));
+        assert_eq!(detector.on_header(&sealed_header(11, 0, &a)), Ok(None));
+        assert_eq!(detector.on_header(&sealed_header(10, 1, &b)), Ok(None));
+
+        detector.prune_before(U256::from(11));
+        assert_eq!(detector.len(), 1);
+        assert!(!detector.is_empty());
+    }
+
+    #[test]
+    fn test_rejects_unsealed_headers() {
+        let mut detector = EquivocationDetector::new(StepSchedule::gnosis());
+        let header = GnosisHeader {
+            aura_step: Some(U256::from(1)),
+            aura_seal: Some(FixedBytes::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(
+            detector.on_header(&header),
+            Err(EquivocationError::Seal(SealError::InvalidSignature))
+        );
+        assert!(detector.is_empty());
+    }
+
+    #[test]
+    fn test_ignores_relabelled_headers() {
+        let key = SigningKey::from_slice(&[1; 32]).unwrap();
+        let mut detector = EquivocationDetector::new(StepSchedule::gnosis());
+        let honest = sealed_header(10, 0, &key);
+        assert_eq!(detector.on_header(&honest), Ok(None));
+
+        // Moving the header to another step keeps a valid seal, but not a matching timestamp
+        let mut relabelled = honest.clone();
+        relabelled.aura_step = Some(U256::from(11));
+        assert_eq!(
+            aura::recover_seal_signer(&relabelled),
+            aura::recover_seal_signer(&honest)
+        );
+        assert!(matches!(
+            detector.on_header(&relabelled),
+            Err(EquivocationError::Step(StepError::TimestampOutsideStep {
+                step: 11,
+                ..
+            }))
+        ));
+
+        let mut detector = EquivocationDetector::new(StepSchedule::gnosis());
+        assert!(detector.on_header(&relabelled).is_err());
+        assert_eq!(detector.on_header(&honest), Ok(None));
+        assert_eq!(detector.len(), 1);
+    }
+}
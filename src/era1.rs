@@ -0,0 +1,658 @@
+use std::io::{self, Read, Write};
+
+use alloy_consensus::{BlockBody, ReceiptEnvelope, TxEnvelope};
+use alloy_primitives::{B256, BlockNumber, U256};
+use alloy_rlp::{Decodable, Encodable};
+use sha2::{Digest, Sha256};
+
+use crate::header::GnosisHeader;
+
+/// Maximum number of blocks in an Era1 file, which is also the length of an epoch.
+pub const MAX_BLOCKS_PER_ERA1: u64 = 8192;
+
+/// Depth of the accumulator tree over one epoch of header records.
+const ACCUMULATOR_DEPTH: usize = 13;
+
+/// Entry type of the version marker that starts every e2store file.
+pub const VERSION: u16 = 0x3265;
+/// Entry type of a snappy-compressed RLP header.
+pub const COMPRESSED_HEADER: u16 = 0x03;
+/// Entry type of a snappy-compressed RLP body.
+pub const COMPRESSED_BODY: u16 = 0x04;
+/// Entry type of snappy-compressed RLP receipts.
+pub const COMPRESSED_RECEIPTS: u16 = 0x05;
+/// Entry type of a little-endian 32-byte total difficulty.
+pub const TOTAL_DIFFICULTY: u16 = 0x06;
+/// Entry type of the epoch accumulator root.
+pub const ACCUMULATOR: u16 = 0x07;
+/// Entry type of the index of block offsets that ends every Era1 file.
+pub const BLOCK_INDEX: u16 = 0x3266;
+
+/// Errors returned when building, reading or verifying an [`Era1File`].
+#[derive(Debug, derive_more::Display, derive_more::Error)]
+pub enum Era1Error {
+    /// Reading or writing the underlying file failed.
+    #[display("{_0}")]
+    Io(io::Error),
+    /// An entry does not hold valid RLP.
+    #[display("{_0}")]
+    Rlp(alloy_rlp::Error),
+    /// The file holds no blocks.
+    #[display("no blocks")]
+    Empty,
+    /// The file holds more than [`MAX_BLOCKS_PER_ERA1`] blocks.
+    #[display("{_0} blocks exceed the epoch length")]
+    TooManyBlocks(#[error(not(source))] usize),
+    /// The first block does not start an epoch.
+    #[display("block {_0} does not start an epoch")]
+    UnalignedStart(#[error(not(source))] BlockNumber),
+    /// Blocks are not consecutive.
+    #[display("expected block {expected}, got {got}")]
+    NotConsecutive {
+        /// The block number that should follow.
+        expected: BlockNumber,
+        /// The block number found.
+        got: BlockNumber,
+    },
+    /// Era1 files only hold pre-merge history.
+    #[display("block {_0} is post-merge")]
+    PostMerge(#[error(not(source))] BlockNumber),
+    /// An entry of another type was expected.
+    #[display("expected entry type {expected:#06x}, got {got:#06x}")]
+    UnexpectedEntry {
+        /// The expected entry type.
+        expected: u16,
+        /// The entry type found.
+        got: u16,
+    },
+    /// An entry has the wrong length for its type.
+    #[display("invalid length {len} for entry type {entry_type:#06x}")]
+    InvalidEntryLength {
+        /// The entry type.
+        entry_type: u16,
+        /// The length found.
+        len: usize,
+    },
+    /// The block index does not match the blocks of the file.
+    #[display("block index does not match the file")]
+    InvalidBlockIndex,
+    /// The total difficulty of a block is not the one of its parent plus its difficulty.
+    #[display("invalid total difficulty at block {_0}")]
+    TotalDifficultyMismatch(#[error(not(source))] BlockNumber),
+    /// The accumulator root does not match the blocks of the file.
+    #[display("accumulator root {got} does not match {expected}")]
+    AccumulatorMismatch {
+        /// The root computed from the blocks.
+        expected: B256,
+        /// The root stored in the file.
+        got: B256,
+    },
+}
+
+/// A single e2store entry: a type, a length and the data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// Type of the entry.
+    pub entry_type: u16,
+    /// Data of the entry.
+    pub data: Vec<u8>,
+}
+
+impl Entry {
+    /// Length of the header preceding the data of every entry.
+    pub const HEADER_LENGTH: usize = 8;
+
+    /// Creates an entry.
+    pub const fn new(entry_type: u16, data: Vec<u8>) -> Self {
+        Self { entry_type, data }
+    }
+
+    /// Returns the length of the entry, header included.
+    pub const fn length(&self) -> usize {
+        Self::HEADER_LENGTH + self.data.len()
+    }
+
+    /// Writes the entry: the type and data length in little-endian, two reserved zero bytes and
+    /// the data.
+    pub fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        let len = u32::try_from(self.data.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "entry too large"))?;
+        out.write_all(&self.entry_type.to_le_bytes())?;
+        out.write_all(&len.to_le_bytes())?;
+        out.write_all(&[0; 2])?;
+        out.write_all(&self.data)
+    }
+
+    /// Reads the next entry, or returns `None` at the end of the file.
+    pub fn read(reader: &mut impl Read) -> io::Result<Option<Self>> {
+        let mut header = [0; Self::HEADER_LENGTH];
+        let mut filled = 0;
+        while filled < header.len() {
+            match reader.read(&mut header[filled..])? {
+                0 if filled == 0 => return Ok(None),
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                read => filled += read,
+            }
+        }
+        if header[6..] != [0; 2] {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "reserved entry bytes are not zero",
+            ));
+        }
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+        // Only allocate what the reader actually holds, not what the header claims
+        let mut data = Vec::new();
+        reader.take(len.into()).read_to_end(&mut data)?;
+        if data.len() != len as usize {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        Ok(Some(Self { entry_type, data }))
+    }
+
+    fn compressed(entry_type: u16, value: &impl Encodable) -> Result<Self, Era1Error> {
+        let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+        encoder
+            .write_all(&alloy_rlp::encode(value))
+            .map_err(Era1Error::Io)?;
+        let data = encoder
+            .into_inner()
+            .map_err(|err| Era1Error::Io(err.into_error()))?;
+        Ok(Self::new(entry_type, data))
+    }
+
+    fn decompressed<T: Decodable>(&self) -> Result<T, Era1Error> {
+        let mut rlp = Vec::new();
+        snap::read::FrameDecoder::new(self.data.as_slice())
+            .read_to_end(&mut rlp)
+            .map_err(Era1Error::Io)?;
+        alloy_rlp::decode_exact(rlp).map_err(Era1Error::Rlp)
+    }
+
+    fn expect(self, entry_type: u16) -> Result<Self, Era1Error> {
+        if self.entry_type != entry_type {
+            return Err(Era1Error::UnexpectedEntry {
+                expected: entry_type,
+                got: self.entry_type,
+            });
+        }
+        Ok(self)
+    }
+
+    fn fixed<const N: usize>(&self) -> Result<[u8; N], Era1Error> {
+        self.data
+            .as_slice()
+            .try_into()
+            .map_err(|_| Era1Error::InvalidEntryLength {
+                entry_type: self.entry_type,
+                len: self.data.len(),
+            })
+    }
+}
+
+/// A block of pre-merge history as stored in an Era1 file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1Block {
+    /// The AuRa header.
+    pub header: GnosisHeader,
+    /// The transactions and ommers.
+    pub body: BlockBody<TxEnvelope, GnosisHeader>,
+    /// The receipts of the transactions.
+    pub receipts: Vec<ReceiptEnvelope>,
+    /// Sum of the difficulties of all blocks up to and including this one.
+    pub total_difficulty: U256,
+}
+
+impl Era1Block {
+    /// Returns the record of this block in the epoch accumulator.
+    pub fn header_record(&self) -> HeaderRecord {
+        HeaderRecord {
+            block_hash: self.header.hash_slow(),
+            total_difficulty: self.total_difficulty,
+        }
+    }
+}
+
+/// An entry of the epoch accumulator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeaderRecord {
+    /// Hash of the header.
+    pub block_hash: B256,
+    /// Total difficulty of the block.
+    pub total_difficulty: U256,
+}
+
+impl HeaderRecord {
+    /// Returns the SSZ hash tree root of the record.
+    pub fn hash_tree_root(&self) -> B256 {
+        sha256_pair(
+            self.block_hash,
+            B256::from(self.total_difficulty.to_le_bytes::<32>()),
+        )
+    }
+}
+
+/// Returns the epoch accumulator root over the given records.
+///
+/// This is the SSZ hash tree root of a `List[HeaderRecord, 8192]`.
+pub fn accumulator_root(records: &[HeaderRecord]) -> B256 {
+    let mut layer: Vec<B256> = records.iter().map(HeaderRecord::hash_tree_root).collect();
+    let mut zero = B256::ZERO;
+    for _ in 0..ACCUMULATOR_DEPTH {
+        if layer.len() % 2 == 1 {
+            layer.push(zero);
+        }
+        layer = layer
+            .chunks(2)
+            .map(|pair| sha256_pair(pair[0], pair[1]))
+            .collect();
+        zero = sha256_pair(zero, zero);
+    }
+    let root = layer.first().copied().unwrap_or(zero);
+
+    let mut length = B256::ZERO;
+    length[..8].copy_from_slice(&(records.len() as u64).to_le_bytes());
+    sha256_pair(root, length)
+}
+
+fn sha256_pair(left: B256, right: B256) -> B256 {
+    B256::from_slice(
+        &Sha256::new()
+            .chain_update(left)
+            .chain_update(right)
+            .finalize(),
+    )
+}
+
+/// One epoch of pre-merge history in the Era1 format.
+///
+/// The file is an e2store file holding a version entry, the compressed header, body, receipts
+/// and total difficulty of every block, the accumulator root of the epoch and an index of the
+/// offsets of the blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Era1File {
+    blocks: Vec<Era1Block>,
+    accumulator: B256,
+}
+
+impl Era1File {
+    /// Creates a file from consecutive pre-merge blocks, starting at the first block of an epoch.
+    pub fn new(blocks: Vec<Era1Block>) -> Result<Self, Era1Error> {
+        Self::check_blocks(&blocks)?;
+        let records: Vec<_> = blocks.iter().map(Era1Block::header_record).collect();
+        Ok(Self {
+            accumulator: accumulator_root(&records),
+            blocks,
+        })
+    }
+
+    /// Returns the blocks of the file.
+    pub fn blocks(&self) -> &[Era1Block] {
+        &self.blocks
+    }
+
+    /// Returns the accumulator root of the file.
+    ///
+    /// For a file that was read, this is the root stored in the file, which
+    /// [`Era1File::verify`] checks against the blocks.
+    pub const fn accumulator(&self) -> B256 {
+        self.accumulator
+    }
+
+    /// Returns the number of the first block.
+    pub fn start_block(&self) -> BlockNumber {
+        self.blocks[0].header.number
+    }
+
+    /// Returns the epoch of the file.
+    pub fn epoch(&self) -> u64 {
+        self.start_block() / MAX_BLOCKS_PER_ERA1
+    }
+
+    /// Returns the conventional file name, such as `gnosis-00000-1234abcd.era1`.
+    pub fn file_name(&self, network: &str) -> String {
+        format!(
+            "{network}-{:05}-{}.era1",
+            self.epoch(),
+            alloy_primitives::hex::encode(&self.accumulator[..4])
+        )
+    }
+
+    /// Checks the total difficulty of every block and the accumulator root.
+    ///
+    /// The total difficulty of the first block can only be checked for the genesis epoch.
+    pub fn verify(&self) -> Result<(), Era1Error> {
+        let mut parent_td = None;
+        for block in &self.blocks {
+            let expected = match parent_td {
+                Some(parent_td) => Some(parent_td + block.header.difficulty),
+                None if block.header.number == 0 => Some(block.header.difficulty),
+                None => None,
+            };
+            if expected.is_some_and(|expected| expected != block.total_difficulty) {
+                return Err(Era1Error::TotalDifficultyMismatch(block.header.number));
+            }
+            parent_td = Some(block.total_difficulty);
+        }
+
+        let records: Vec<_> = self.blocks.iter().map(Era1Block::header_record).collect();
+        let expected = accumulator_root(&records);
+        if expected != self.accumulator {
+            return Err(Era1Error::AccumulatorMismatch {
+                expected,
+                got: self.accumulator,
+            });
+        }
+        Ok(())
+    }
+
+    /// Writes the file.
+    pub fn write(&self, out: &mut impl Write) -> Result<(), Era1Error> {
+        let mut entries = vec![Entry::new(VERSION, Vec::new())];
+        let mut position = Entry::HEADER_LENGTH as u64;
+        let mut offsets = Vec::with_capacity(self.blocks.len());
+        for block in &self.blocks {
+            offsets.push(position);
+            let block_entries = [
+                Entry::compressed(COMPRESSED_HEADER, &block.header)?,
+                Entry::compressed(COMPRESSED_BODY, &block.body)?,
+                Entry::compressed(COMPRESSED_RECEIPTS, &block.receipts)?,
+                Entry::new(
+                    TOTAL_DIFFICULTY,
+                    block.total_difficulty.to_le_bytes::<32>().to_vec(),
+                ),
+            ];
+            position += block_entries
+                .iter()
+                .map(|entry| entry.length() as u64)
+                .sum::<u64>();
+            entries.extend(block_entries);
+        }
+        let accumulator = Entry::new(ACCUMULATOR, self.accumulator.to_vec());
+        position += accumulator.length() as u64;
+        entries.push(accumulator);
+
+        // Offsets are relative to the start of the block index entry
+        let mut index = self.start_block().to_le_bytes().to_vec();
+        for offset in offsets {
+            index.extend_from_slice(&(offset as i64 - position as i64).to_le_bytes());
+        }
+        index.extend_from_slice(&(self.blocks.len() as u64).to_le_bytes());
+        entries.push(Entry::new(BLOCK_INDEX, index));
+
+        for entry in entries {
+            entry.write(out).map_err(Era1Error::Io)?;
+        }
+        Ok(())
+    }
+
+    /// Reads a file, checking its structure and block index.
+    ///
+    /// Entries of unknown types between the blocks and the accumulator are skipped. The
+    /// accumulator root is not checked, see [`Era1File::verify`].
+    pub fn read(reader: &mut impl Read) -> Result<Self, Era1Error> {
+        let mut position = 0;
+        Self::next_entry(reader, &mut position)?.expect(VERSION)?;
+
+        let mut blocks = Vec::new();
+        let mut offsets = Vec::new();
+        let accumulator = loop {
+            let entry_position = position;
+            let entry = Self::next_entry(reader, &mut position)?;
+            match entry.entry_type {
+                COMPRESSED_HEADER => {
+                    offsets.push(entry_position);
+                    let header = entry.decompressed()?;
+                    let body = Self::next_entry(reader, &mut position)?
+                        .expect(COMPRESSED_BODY)?
+                        .decompressed()?;
+                    let receipts = Self::next_entry(reader, &mut position)?
+                        .expect(COMPRESSED_RECEIPTS)?
+                        .decompressed()?;
+                    let total_difficulty = Self::next_entry(reader, &mut position)?
+                        .expect(TOTAL_DIFFICULTY)?
+                        .fixed::<32>()?;
+                    blocks.push(Era1Block {
+                        header,
+                        body,
+                        receipts,
+                        total_difficulty: U256::from_le_bytes(total_difficulty),
+                    });
+                }
+                ACCUMULATOR => break B256::from(entry.fixed::<32>()?),
+                _ => {}
+            }
+        };
+        Self::check_blocks(&blocks)?;
+
+        let index_position = position;
+        let index = Self::next_entry(reader, &mut position)?.expect(BLOCK_INDEX)?;
+        if index.data.len() != 16 + 8 * blocks.len() {
+            return Err(Era1Error::InvalidEntryLength {
+                entry_type: BLOCK_INDEX,
+                len: index.data.len(),
+            });
+        }
+        let word = |i: usize| {
+            let bytes = index.data[i * 8..(i + 1) * 8].try_into().unwrap();
+            i64::from_le_bytes(bytes)
+        };
+        let start_matches = word(0) as u64 == blocks[0].header.number;
+        let count_matches = word(blocks.len() + 1) as u64 == blocks.len() as u64;
+        let offsets_match = offsets
+            .iter()
+            .enumerate()
+            .all(|(i, offset)| *offset as i64 - index_position as i64 == word(i + 1));
+        if !(start_matches && count_matches && offsets_match) {
+            return Err(Era1Error::InvalidBlockIndex);
+        }
+
+        Ok(Self {
+            blocks,
+            accumulator,
+        })
+    }
+
+    /// Reads the next entry, advancing `position` past it.
+    fn next_entry(reader: &mut impl Read, position: &mut u64) -> Result<Entry, Era1Error> {
+        let entry = Entry::read(reader)
+            .map_err(Era1Error::Io)?
+            .ok_or_else(|| Era1Error::Io(io::ErrorKind::UnexpectedEof.into()))?;
+        *position += entry.length() as u64;
+        Ok(entry)
+    }
+
+    fn check_blocks(blocks: &[Era1Block]) -> Result<(), Era1Error> {
+        let first = blocks.first().ok_or(Era1Error::Empty)?.header.number;
+        if blocks.len() as u64 > MAX_BLOCKS_PER_ERA1 {
+            return Err(Era1Error::TooManyBlocks(blocks.len()));
+        }
+        if first % MAX_BLOCKS_PER_ERA1 != 0 {
+            return Err(Era1Error::UnalignedStart(first));
+        }
+        for (expected, block) in (first..).zip(blocks) {
+            if block.header.number != expected {
+                return Err(Era1Error::NotConsecutive {
+                    expected,
+                    got: block.header.number,
+                });
+            }
+            if !block.header.is_pre_merge() {
+                return Err(Era1Error::PostMerge(expected));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestChainConfig, TestChainGenerator};
+    use alloy_consensus::{Eip658Value, Receipt, ReceiptWithBloom};
+    use alloy_primitives::b256;
+
+    /// Builds the first `count` blocks of a chain whose merge happens after them.
+    fn era1_blocks(count: usize) -> Vec<Era1Block> {
+        let mut generator = TestChainGenerator::new(TestChainConfig {
+            with_bodies: true,
+            merge_block: count as u64 + 1,
+            ..Default::default()
+        });
+        let genesis = generator.genesis().clone();
+        let mut blocks = vec![Era1Block {
+            total_difficulty: genesis.difficulty,
+            header: genesis,
+            body: BlockBody::default(),
+            receipts: Vec::new(),
+        }];
+        for block in generator.blocks(count - 1) {
+            let body = block.body.unwrap();
+            let receipts = body
+                .transactions
+                .iter()
+                .enumerate()
+                .map(|(i, _)| {
+                    ReceiptEnvelope::Legacy(ReceiptWithBloom::new(
+                        Receipt {
+                            status: Eip658Value::Eip658(true),
+                            cumulative_gas_used: 21_000 * (i as u64 + 1),
+                            logs: Vec::new(),
+                        },
+                        Default::default(),
+                    ))
+                })
+                .collect();
+            blocks.push(Era1Block {
+                total_difficulty: blocks.last().unwrap().total_difficulty + block.header.difficulty,
+                header: block.header,
+                body,
+                receipts,
+            });
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_entry_roundtrip() {
+        let entry = Entry::new(TOTAL_DIFFICULTY, vec![1; 32]);
+        let mut encoded = Vec::new();
+        entry.write(&mut encoded).unwrap();
+        assert_eq!(
+            encoded[..8],
+            [0x06, 0x00, 0x20, 0x00, 0x00, 0x00, 0x00, 0x00]
+        );
+        assert_eq!(encoded.len(), entry.length());
+
+        let mut reader = encoded.as_slice();
+        assert_eq!(Entry::read(&mut reader).unwrap(), Some(entry));
+        assert_eq!(Entry::read(&mut reader).unwrap(), None);
+        assert!(Entry::read(&mut &encoded[..4]).is_err());
+        assert_eq!(
+            Entry::read(&mut &encoded[..20]).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+
+        // A length far beyond the data is rejected without allocating it
+        let huge = [0x06, 0x00, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x01];
+        assert_eq!(
+            Entry::read(&mut huge.as_slice()).unwrap_err().kind(),
+            io::ErrorKind::UnexpectedEof
+        );
+    }
+
+    #[test]
+    fn test_accumulator_root() {
+        // Root of an empty list: the zero subtree of depth 13 mixed in with a length of zero
+        assert_eq!(
+            accumulator_root(&[]),
+            b256!("4a8c3a07c8d23adc5bac61157555c3c784d53d9bc110c1370809bd23cd93777d")
+        );
+
+        let record = HeaderRecord {
+            block_hash: B256::repeat_byte(0x11),
+            total_difficulty: U256::from(0x2a),
+        };
+        assert_ne!(accumulator_root(&[record]), accumulator_root(&[]));
+        assert_ne!(
+            accumulator_root(&[record]),
+            accumulator_root(&[record, record])
+        );
+    }
+
+    #[test]
+    fn test_write_read_verify() {
+        let file = Era1File::new(era1_blocks(8)).unwrap();
+        assert_eq!(file.epoch(), 0);
+        file.verify().unwrap();
+
+        let mut encoded = Vec::new();
+        file.write(&mut encoded).unwrap();
+        let read = Era1File::read(&mut encoded.as_slice()).unwrap();
+        assert_eq!(read, file);
+        read.verify().unwrap();
+
+        let name = file.file_name("gnosis");
+        assert!(name.starts_with("gnosis-00000-"));
+        assert!(name.ends_with(".era1"));
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let mut blocks = era1_blocks(4);
+        blocks[2].total_difficulty += U256::from(1);
+        let file = Era1File::new(blocks).unwrap();
+        assert!(matches!(
+            file.verify(),
+            Err(Era1Error::TotalDifficultyMismatch(2))
+        ));
+
+        let file = Era1File {
+            accumulator: B256::ZERO,
+            ..Era1File::new(era1_blocks(4)).unwrap()
+        };
+        assert!(matches!(
+            file.verify(),
+            Err(Era1Error::AccumulatorMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_files() {
+        assert!(matches!(Era1File::new(Vec::new()), Err(Era1Error::Empty)));
+
+        let mut blocks = era1_blocks(4);
+        blocks.remove(1);
+        assert!(matches!(
+            Era1File::new(blocks),
+            Err(Era1Error::NotConsecutive {
+                expected: 1,
+                got: 2
+            })
+        ));
+
+        let blocks = era1_blocks(4).split_off(1);
+        assert!(matches!(
+            Era1File::new(blocks),
+            Err(Era1Error::UnalignedStart(1))
+        ));
+
+        let mut encoded = Vec::new();
+        Era1File::new(era1_blocks(4))
+            .unwrap()
+            .write(&mut encoded)
+            .unwrap();
+        // Corrupt the count at the end of the block index
+        let last = encoded.len() - 8;
+        encoded[last] = 5;
+        assert!(matches!(
+            Era1File::read(&mut encoded.as_slice()),
+            Err(Era1Error::InvalidBlockIndex)
+        ));
+        assert!(matches!(
+            Era1File::read(&mut &encoded[..40]),
+            Err(Era1Error::Io(_))
+        ));
+    }
+}
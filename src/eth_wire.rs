@@ -0,0 +1,415 @@
+use alloy_consensus::{Block, TxEnvelope};
+use alloy_eips::BlockHashOrNumber;
+use alloy_primitives::{B256, BlockNumber, U256};
+use alloy_rlp::{
+    BufMut, Decodable, Encodable, RlpDecodable, RlpDecodableWrapper, RlpEncodable,
+    RlpEncodableWrapper,
+};
+
+use crate::{
+    fork_id::{ForkId, ForkSchedule, ValidationError},
+    header::GnosisHeader,
+};
+
+/// Version of the `eth` protocol these messages follow.
+pub const ETH_68: u8 = 68;
+
+/// A Gnosis block, as carried by [`NewBlock`].
+pub type GnosisBlock = Block<TxEnvelope, GnosisHeader>;
+
+/// Errors returned when decoding an [`EthMessage`].
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum MessageError {
+    /// The message id is not one of the supported messages.
+    #[display("unknown message id {_0:#04x}")]
+    UnknownMessageId(#[error(not(source))] u8),
+    /// The message payload is not valid RLP for its type.
+    #[display("{_0}")]
+    Rlp(alloy_rlp::Error),
+}
+
+/// Errors returned when checking the [`Status`] of a remote peer.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum StatusError {
+    /// The peer speaks another version of the protocol.
+    #[display("expected eth/{expected}, got eth/{got}")]
+    VersionMismatch {
+        /// The local protocol version.
+        expected: u8,
+        /// The version announced by the peer.
+        got: u8,
+    },
+    /// The peer is on another network.
+    #[display("expected chain {expected}, got {got}")]
+    ChainMismatch {
+        /// The local chain id.
+        expected: u64,
+        /// The chain id announced by the peer.
+        got: u64,
+    },
+    /// The peer has another genesis block.
+    #[display("expected genesis {expected}, got {got}")]
+    GenesisMismatch {
+        /// The local genesis hash.
+        expected: B256,
+        /// The genesis hash announced by the peer.
+        got: B256,
+    },
+    /// The fork identifier of the peer is not compatible with the local head.
+    #[display("{_0}")]
+    ForkId(ValidationError),
+}
+
+/// The `Status` handshake message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct Status {
+    /// Version of the `eth` protocol.
+    pub version: u8,
+    /// Chain id of the network.
+    pub chain: u64,
+    /// Total difficulty of the head block.
+    ///
+    /// Every AuRa block adds close to `u128::MAX`, and the value is frozen at the merge.
+    pub total_difficulty: U256,
+    /// Hash of the head block.
+    pub blockhash: B256,
+    /// Hash of the genesis block.
+    pub genesis: B256,
+    /// Fork identifier at the head block.
+    pub forkid: ForkId,
+}
+
+impl Status {
+    /// Creates the `eth/68` status for a node at `head` on the chain described by `schedule`.
+    pub fn new(
+        chain: u64,
+        schedule: &ForkSchedule,
+        head: &GnosisHeader,
+        total_difficulty: U256,
+    ) -> Self {
+        Self {
+            version: ETH_68,
+            chain,
+            total_difficulty,
+            blockhash: head.hash_slow(),
+            genesis: schedule.genesis_hash,
+            forkid: schedule.fork_id_at(head),
+        }
+    }
+
+    /// Checks the status of a remote peer against this local status.
+    ///
+    /// `head` is the local head block, whose number and timestamp decide which remote fork
+    /// identifiers are compatible.
+    pub fn validate_remote(
+        &self,
+        schedule: &ForkSchedule,
+        head: &GnosisHeader,
+        remote: &Self,
+    ) -> Result<(), StatusError> {
+        if remote.version != self.version {
+            return Err(StatusError::VersionMismatch {
+                expected: self.version,
+                got: remote.version,
+            });
+        }
+        if remote.chain != self.chain {
+            return Err(StatusError::ChainMismatch {
+                expected: self.chain,
+                got: remote.chain,
+            });
+        }
+        if remote.genesis != self.genesis {
+            return Err(StatusError::GenesisMismatch {
+                expected: self.genesis,
+                got: remote.genesis,
+            });
+        }
+        schedule
+            .validate(head.number, head.timestamp, remote.forkid)
+            .map_err(StatusError::ForkId)
+    }
+}
+
+/// A block announced by hash and number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct BlockHashNumber {
+    /// Hash of the block.
+    pub hash: B256,
+    /// Number of the block.
+    pub number: BlockNumber,
+}
+
+/// The `NewBlockHashes` message, announcing blocks without their content.
+#[derive(Debug, Clone, PartialEq, Eq, Default, RlpEncodableWrapper, RlpDecodableWrapper)]
+pub struct NewBlockHashes(pub Vec<BlockHashNumber>);
+
+/// The `GetBlockHeaders` request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct GetBlockHeaders {
+    /// First block of the range.
+    pub start_block: BlockHashOrNumber,
+    /// Maximum number of headers to return.
+    pub limit: u64,
+    /// Number of blocks to skip between two consecutive headers.
+    pub skip: u32,
+    /// Whether to walk towards the genesis block instead of the head.
+    pub reverse: bool,
+}
+
+/// The `BlockHeaders` response.
+///
+/// AuRa and post-merge headers can be mixed freely, each header is decoded with the seal fields
+/// it was encoded with.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper)]
+pub struct BlockHeaders<H = GnosisHeader>(pub Vec<H>);
+
+impl<H> Default for BlockHeaders<H> {
+    fn default() -> Self {
+        Self(Vec::new())
+    }
+}
+
+/// The `NewBlock` message, propagating a full block.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct NewBlock<B = GnosisBlock> {
+    /// The propagated block.
+    pub block: B,
+    /// Total difficulty including the block.
+    pub td: U256,
+}
+
+/// A request or response, tagged with the id that pairs them.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct RequestPair<T> {
+    /// Id chosen by the requesting peer and echoed in the response.
+    pub request_id: u64,
+    /// The message itself.
+    pub message: T,
+}
+
+/// An `eth/68` message exchanged with a Gnosis peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EthMessage<H = GnosisHeader, B = GnosisBlock> {
+    /// Handshake status.
+    Status(Status),
+    /// Block announcements.
+    NewBlockHashes(NewBlockHashes),
+    /// Request for a range of headers.
+    GetBlockHeaders(RequestPair<GetBlockHeaders>),
+    /// Response to a [`GetBlockHeaders`] request.
+    BlockHeaders(RequestPair<BlockHeaders<H>>),
+    /// Block propagation.
+    NewBlock(Box<NewBlock<B>>),
+}
+
+impl<H, B> EthMessage<H, B> {
+    /// Id of the `Status` message.
+    pub const STATUS: u8 = 0x00;
+    /// Id of the `NewBlockHashes` message.
+    pub const NEW_BLOCK_HASHES: u8 = 0x01;
+    /// Id of the `GetBlockHeaders` message.
+    pub const GET_BLOCK_HEADERS: u8 = 0x03;
+    /// Id of the `BlockHeaders` message.
+    pub const BLOCK_HEADERS: u8 = 0x04;
+    /// Id of the `NewBlock` message.
+    pub const NEW_BLOCK: u8 = 0x07;
+
+    /// Returns the message id, relative to the start of the `eth` capability.
+    pub const fn message_id(&self) -> u8 {
+        match self {
+            Self::Status(_) => Self::STATUS,
+            Self::NewBlockHashes(_) => Self::NEW_BLOCK_HASHES,
+            Self::GetBlockHeaders(_) => Self::GET_BLOCK_HEADERS,
+            Self::BlockHeaders(_) => Self::BLOCK_HEADERS,
+            Self::NewBlock(_) => Self::NEW_BLOCK,
+        }
+    }
+}
+
+impl<H: Encodable, B: Encodable> EthMessage<H, B> {
+    /// Encodes the message id followed by the RLP payload, as sent before compression.
+    pub fn encode(&self, out: &mut dyn BufMut) {
+        self.message_id().encode(out);
+        match self {
+            Self::Status(status) => status.encode(out),
+            Self::NewBlockHashes(hashes) => hashes.encode(out),
+            Self::GetBlockHeaders(request) => request.encode(out),
+            Self::BlockHeaders(response) => response.encode(out),
+            Self::NewBlock(block) => block.encode(out),
+        }
+    }
+
+    /// Returns the encoding of [`EthMessage::encode`].
+    pub fn encoded(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.encode(&mut out);
+        out
+    }
+}
+
+impl<H: Decodable, B: Decodable> EthMessage<H, B> {
+    /// Decodes a message id followed by its RLP payload.
+    pub fn decode(buf: &mut &[u8]) -> Result<Self, MessageError> {
+        let id = u8::decode(buf).map_err(MessageError::Rlp)?;
+        let message = match id {
+            Self::STATUS => Status::decode(buf).map(Self::Status),
+            Self::NEW_BLOCK_HASHES => NewBlockHashes::decode(buf).map(Self::NewBlockHashes),
+            Self::GET_BLOCK_HEADERS => RequestPair::decode(buf).map(Self::GetBlockHeaders),
+            Self::BLOCK_HEADERS => RequestPair::decode(buf).map(Self::BlockHeaders),
+            Self::NEW_BLOCK => NewBlock::decode(buf).map(|block| Self::NewBlock(Box::new(block))),
+            id => return Err(MessageError::UnknownMessageId(id)),
+        };
+        message.map_err(MessageError::Rlp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TestChainConfig, TestChainGenerator};
+    use alloy_primitives::hex;
+
+    fn generator() -> TestChainGenerator {
+        TestChainGenerator::new(TestChainConfig {
+            with_bodies: true,
+            ..Default::default()
+        })
+    }
+
+    fn roundtrip(message: EthMessage) {
+        let encoded = message.encoded();
+        assert_eq!(EthMessage::decode(&mut encoded.as_slice()), Ok(message));
+    }
+
+    #[test]
+    fn test_block_headers_mixing_aura_and_pos() {
+        let mut generator = generator();
+        // Spans the merge at block 10
+        let headers = generator.headers(12);
+        assert!(headers[0].is_pre_merge());
+        assert!(headers[11].is_post_merge());
+
+        let response = RequestPair {
+            request_id: 7,
+            message: BlockHeaders(headers),
+        };
+        let encoded = alloy_rlp::encode(&response);
+        assert_eq!(
+            RequestPair::decode(&mut encoded.as_slice()),
+            Ok(response.clone())
+        );
+        roundtrip(EthMessage::BlockHeaders(response));
+    }
+
+    #[test]
+    fn test_get_block_headers_encoding() {
+        // Test vector from EIP-2481
+        let request = RequestPair {
+            request_id: 1111,
+            message: GetBlockHeaders {
+                start_block: BlockHashOrNumber::Hash(B256::from(U256::from(0xdeadc0de_u64))),
+                limit: 5,
+                skip: 5,
+                reverse: false,
+            },
+        };
+        let expected = hex!(
+            "e8820457e4a000000000000000000000000000000000000000000000000000000000deadc0de050580"
+        );
+        assert_eq!(alloy_rlp::encode(&request), expected);
+        assert_eq!(RequestPair::decode(&mut &expected[..]), Ok(request));
+
+        roundtrip(EthMessage::GetBlockHeaders(RequestPair {
+            request_id: 1,
+            message: GetBlockHeaders {
+                start_block: BlockHashOrNumber::Number(9_186_425),
+                limit: 192,
+                skip: 0,
+                reverse: true,
+            },
+        }));
+    }
+
+    #[test]
+    fn test_new_block() {
+        let mut generator = generator();
+        for block in generator.blocks(14) {
+            let message = NewBlock {
+                td: U256::from(block.header.number),
+                block: GnosisBlock::new(block.header, block.body.unwrap()),
+            };
+            roundtrip(EthMessage::NewBlock(Box::new(message)));
+        }
+    }
+
+    #[test]
+    fn test_new_block_hashes() {
+        let mut generator = generator();
+        let hashes = generator
+            .blocks(3)
+            .into_iter()
+            .map(|block| BlockHashNumber {
+                hash: block.header.hash_slow(),
+                number: block.header.number,
+            })
+            .collect();
+        roundtrip(EthMessage::NewBlockHashes(NewBlockHashes(hashes)));
+    }
+
+    #[test]
+    fn test_status_handshake() {
+        let mut generator = generator();
+        let schedule = ForkSchedule::new(generator.genesis(), []);
+        let head = generator.blocks(12).pop().unwrap().header;
+
+        let local = Status::new(100, &schedule, &head, U256::from(12));
+        assert_eq!(local.version, ETH_68);
+        assert_eq!(local.genesis, generator.genesis().hash_slow());
+        roundtrip(EthMessage::Status(local));
+        assert_eq!(local.validate_remote(&schedule, &head, &local), Ok(()));
+
+        let remote = Status {
+            chain: 10200,
+            ..local
+        };
+        assert_eq!(
+            local.validate_remote(&schedule, &head, &remote),
+            Err(StatusError::ChainMismatch {
+                expected: 100,
+                got: 10200
+            })
+        );
+
+        let remote = Status {
+            genesis: B256::ZERO,
+            forkid: ForkSchedule::gnosis().fork_id(0, 0),
+            ..local
+        };
+        assert!(matches!(
+            local.validate_remote(&schedule, &head, &remote),
+            Err(StatusError::GenesisMismatch { .. })
+        ));
+
+        let remote = Status {
+            forkid: ForkSchedule::gnosis().fork_id(0, 0),
+            ..local
+        };
+        assert!(matches!(
+            local.validate_remote(&schedule, &head, &remote),
+            Err(StatusError::ForkId(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(
+            EthMessage::<GnosisHeader, GnosisBlock>::decode(&mut &[0x02, 0xc0][..]),
+            Err(MessageError::UnknownMessageId(0x02))
+        );
+        assert!(matches!(
+            EthMessage::<GnosisHeader, GnosisBlock>::decode(&mut &[0x04, 0xc1][..]),
+            Err(MessageError::Rlp(_))
+        ));
+    }
+}
@@ -0,0 +1,306 @@
+use alloy_consensus::TxReceipt;
+use alloy_primitives::{Address, BlockNumber, U256, address};
+
+use crate::{blob_schedule::BlobSchedule, header::GnosisHeader};
+
+/// Fee collector of Gnosis Chain mainnet.
+pub const GNOSIS_FEE_COLLECTOR: Address = address!("6bbe78ee9e474842dbd4ab4987b3cefe88426a92");
+
+/// Fee collector of the Chiado testnet.
+pub const CHIADO_FEE_COLLECTOR: Address = address!("1559000000000000000000000000000000000000");
+
+/// Where and from when Gnosis credits fees that Ethereum burns.
+///
+/// From the fee collector transition onward, the EIP-1559 base fee of every transaction is
+/// credited to the fee collector instead of being burned. From the blob fee transition onward, the
+/// same applies to the EIP-4844 blob fee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeCollector {
+    /// Address credited with the collected fees.
+    pub address: Address,
+    /// First block whose base fees are collected.
+    pub base_fee_transition: BlockNumber,
+    /// Timestamp of the first block whose blob fees are collected, if blob fees are collected.
+    pub blob_fee_transition: Option<u64>,
+}
+
+impl FeeCollector {
+    /// Returns the fee collector of Gnosis Chain mainnet.
+    ///
+    /// Base fees are collected since London, blob fees since Prague.
+    pub const fn gnosis() -> Self {
+        Self {
+            address: GNOSIS_FEE_COLLECTOR,
+            base_fee_transition: 19_040_000,
+            blob_fee_transition: Some(1_746_021_820),
+        }
+    }
+
+    /// Returns the fee collector of the Chiado testnet.
+    ///
+    /// Base fees are collected since genesis, blob fees since Prague.
+    pub const fn chiado() -> Self {
+        Self {
+            address: CHIADO_FEE_COLLECTOR,
+            base_fee_transition: 0,
+            blob_fee_transition: Some(1_741_254_220),
+        }
+    }
+
+    /// Returns the fee collector for a known Gnosis chain id.
+    pub const fn for_chain(chain_id: u64) -> Option<Self> {
+        match chain_id {
+            100 => Some(Self::gnosis()),
+            10200 => Some(Self::chiado()),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the base fees of `header` are credited to the fee collector.
+    pub fn collects_base_fee(&self, header: &GnosisHeader) -> bool {
+        header.number >= self.base_fee_transition && header.base_fee_per_gas.is_some()
+    }
+
+    /// Returns `true` if the blob fees of `header` are credited to the fee collector.
+    pub fn collects_blob_fee(&self, header: &GnosisHeader) -> bool {
+        self.blob_fee_transition
+            .is_some_and(|transition| header.timestamp >= transition)
+            && header.cancun_active()
+    }
+
+    /// Computes the fees credited to the fee collector for a block.
+    ///
+    /// The receipts are used to check that the header's gas used matches the gas actually consumed
+    /// by the block's transactions. The blob fee is priced with the [`BlobSchedule`] entry in
+    /// effect for the block.
+    pub fn collected_fees<R: TxReceipt>(
+        &self,
+        header: &GnosisHeader,
+        receipts: &[R],
+        blob_schedule: &BlobSchedule,
+    ) -> Result<CollectedFees, FeeCollectorError> {
+        let receipts_gas_used = receipts.last().map_or(0, |r| r.cumulative_gas_used());
+        if receipts_gas_used != header.gas_used {
+            return Err(FeeCollectorError::GasUsedMismatch {
+                header: header.gas_used,
+                receipts: receipts_gas_used,
+            });
+        }
+
+        let mut fees = CollectedFees::default();
+        if self.collects_base_fee(header) {
+            let base_fee = header.base_fee_per_gas.unwrap_or_default();
+            fees.base_fees = U256::from(base_fee) * U256::from(header.gas_used);
+        }
+        if self.collects_blob_fee(header) {
+            let blob_fee = header
+                .scheduled_blob_fee(blob_schedule)
+                .ok_or(FeeCollectorError::MissingBlobParams)?;
+            let blob_gas_used = header.blob_gas_used.unwrap_or_default();
+            fees.blob_fees = U256::from(blob_fee) * U256::from(blob_gas_used);
+        }
+        Ok(fees)
+    }
+
+    /// Checks that the fee collector's balance changed by exactly the fees collected in the block.
+    ///
+    /// This assumes the fee collector is not otherwise touched by the block, for example by
+    /// receiving a transfer.
+    pub fn verify_balance_delta<R: TxReceipt>(
+        &self,
+        header: &GnosisHeader,
+        receipts: &[R],
+        blob_schedule: &BlobSchedule,
+        balance_before: U256,
+        balance_after: U256,
+    ) -> Result<CollectedFees, FeeCollectorError> {
+        let fees = self.collected_fees(header, receipts, blob_schedule)?;
+        let expected = balance_before.saturating_add(fees.total());
+        if balance_after != expected {
+            return Err(FeeCollectorError::BalanceMismatch {
+                expected,
+                got: balance_after,
+            });
+        }
+        Ok(fees)
+    }
+}
+
+/// Fees credited to the fee collector for a single block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CollectedFees {
+    /// EIP-1559 base fees, `base_fee_per_gas * gas_used`.
+    pub base_fees: U256,
+    /// EIP-4844 blob fees, `blob_base_fee * blob_gas_used`.
+    pub blob_fees: U256,
+}
+
+impl CollectedFees {
+    /// Returns the total amount credited to the fee collector.
+    pub fn total(&self) -> U256 {
+        self.base_fees + self.blob_fees
+    }
+}
+
+/// Errors returned when accounting for collected fees.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum FeeCollectorError {
+    /// The gas used in the header does not match the cumulative gas used of the last receipt.
+    #[display("gas used mismatch: header {header}, receipts {receipts}")]
+    GasUsedMismatch {
+        /// Gas used according to the header.
+        header: u64,
+        /// Gas used according to the receipts.
+        receipts: u64,
+    },
+    /// No blob parameters are scheduled for the block, so its blob fee cannot be priced.
+    #[display("no blob parameters scheduled for block")]
+    MissingBlobParams,
+    /// The post-state balance of the fee collector is not the expected one.
+    #[display("fee collector balance mismatch: expected {expected}, got {got}")]
+    BalanceMismatch {
+        /// Balance expected after the block.
+        expected: U256,
+        /// Balance found after the block.
+        got: U256,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blob_schedule::GNOSIS_BLOB_PARAMS;
+    use alloy_consensus::{Eip658Value, Receipt};
+    use alloy_primitives::{B64, B256, Log};
+
+    fn receipts(gas_used: &[u64]) -> Vec<Receipt<Log>> {
+        let mut cumulative_gas_used = 0;
+        gas_used
+            .iter()
+            .map(|gas| {
+                cumulative_gas_used += gas;
+                Receipt {
+                    status: Eip658Value::Eip658(true),
+                    cumulative_gas_used,
+                    logs: vec![],
+                }
+            })
+            .collect()
+    }
+
+    fn london_header() -> GnosisHeader {
+        GnosisHeader {
+            number: 19_040_000,
+            timestamp: 1_640_000_000,
+            gas_used: 63_000,
+            base_fee_per_gas: Some(7),
+            aura_step: Some(U256::from(1)),
+            aura_seal: Some(Default::default()),
+            ..Default::default()
+        }
+    }
+
+    fn prague_header() -> GnosisHeader {
+        GnosisHeader {
+            number: 40_000_000,
+            timestamp: 1_746_021_820,
+            gas_used: 21_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            mix_hash: Some(B256::ZERO),
+            nonce: Some(B64::ZERO),
+            withdrawals_root: Some(B256::ZERO),
+            blob_gas_used: Some(131_072),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::ZERO),
+            requests_hash: Some(B256::ZERO),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_base_fee_collection() {
+        let collector = FeeCollector::gnosis();
+        let header = london_header();
+        let fees = collector
+            .collected_fees(
+                &header,
+                &receipts(&[21_000, 42_000]),
+                &BlobSchedule::gnosis(),
+            )
+            .unwrap();
+        assert_eq!(fees.base_fees, U256::from(7 * 63_000));
+        assert_eq!(fees.blob_fees, U256::ZERO);
+
+        // Nothing is collected before the transition
+        let mut before = header;
+        before.number -= 1;
+        assert!(!collector.collects_base_fee(&before));
+    }
+
+    #[test]
+    fn test_blob_fee_collection() {
+        let collector = FeeCollector::gnosis();
+        let header = prague_header();
+        let fees = collector
+            .collected_fees(&header, &receipts(&[21_000]), &BlobSchedule::gnosis())
+            .unwrap();
+        assert_eq!(fees.base_fees, U256::from(21_000_000_000_000u64));
+        // No excess blob gas, so the blob fee is the Gnosis minimum of 1 gwei
+        assert_eq!(
+            fees.blob_fees,
+            U256::from(GNOSIS_BLOB_PARAMS.min_blob_fee) * U256::from(131_072)
+        );
+        assert_eq!(fees.total(), fees.base_fees + fees.blob_fees);
+
+        assert_eq!(
+            collector.collected_fees(&header, &receipts(&[21_000]), &BlobSchedule::default()),
+            Err(FeeCollectorError::MissingBlobParams)
+        );
+    }
+
+    #[test]
+    fn test_gas_used_mismatch() {
+        let collector = FeeCollector::chiado();
+        assert_eq!(
+            collector.collected_fees(
+                &london_header(),
+                &receipts(&[21_000]),
+                &BlobSchedule::chiado()
+            ),
+            Err(FeeCollectorError::GasUsedMismatch {
+                header: 63_000,
+                receipts: 21_000
+            })
+        );
+    }
+
+    #[test]
+    fn test_verify_balance_delta() {
+        let collector = FeeCollector::gnosis();
+        let header = london_header();
+        let receipts = receipts(&[63_000]);
+        let schedule = BlobSchedule::gnosis();
+        let before = U256::from(1_000);
+        let after = before + U256::from(7 * 63_000);
+
+        assert!(
+            collector
+                .verify_balance_delta(&header, &receipts, &schedule, before, after)
+                .is_ok()
+        );
+        assert_eq!(
+            collector.verify_balance_delta(&header, &receipts, &schedule, before, before),
+            Err(FeeCollectorError::BalanceMismatch {
+                expected: after,
+                got: before
+            })
+        );
+    }
+
+    #[test]
+    fn test_for_chain() {
+        assert_eq!(FeeCollector::for_chain(100), Some(FeeCollector::gnosis()));
+        assert_eq!(FeeCollector::for_chain(10200), Some(FeeCollector::chiado()));
+        assert_eq!(FeeCollector::for_chain(1), None);
+    }
+}
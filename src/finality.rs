@@ -0,0 +1,309 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use alloy_eips::BlockNumHash;
+use alloy_primitives::{Address, BlockNumber};
+
+use crate::{
+    aura::{self, SealError},
+    header::GnosisHeader,
+};
+
+/// Share of the validator set that has to build on a block for it to be final.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FinalityThreshold {
+    /// More than half of the validators.
+    Majority,
+    /// More than two-thirds of the validators.
+    TwoThirds,
+}
+
+impl FinalityThreshold {
+    /// Returns `true` if `signers` distinct validators out of `validators` reach the threshold.
+    pub const fn is_reached(self, signers: usize, validators: usize) -> bool {
+        match self {
+            Self::Majority => signers * 2 > validators,
+            Self::TwoThirds => signers * 3 > validators * 2,
+        }
+    }
+}
+
+/// Errors returned by the [`FinalityTracker`].
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum FinalityError {
+    /// Headers were not fed in order.
+    #[display("expected block {expected}, got {got}")]
+    OutOfOrder {
+        /// The next block number the tracker expects.
+        expected: BlockNumber,
+        /// The block number that was passed.
+        got: BlockNumber,
+    },
+    /// The seal of the header could not be recovered.
+    #[display("{_0}")]
+    Seal(SealError),
+    /// The header was sealed by an address outside of the validator set.
+    #[display("block {block} sealed by {signer}, which is not a validator")]
+    UnknownSigner {
+        /// Number of the block.
+        block: BlockNumber,
+        /// The recovered signer.
+        signer: Address,
+    },
+}
+
+/// Tracks AuRa finality over a chain of pre-merge headers.
+///
+/// This is the rolling finality of OpenEthereum and Nethermind. Headers are queued with the
+/// validator that sealed them, and the oldest queued block becomes final as soon as the distinct
+/// signers of the queued blocks reach the [`FinalityThreshold`] of the validator set. Blocks
+/// queued under a previous validator set are dropped when the set changes, they only become final
+/// once a later block is.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FinalityTracker {
+    /// Block from which more than two-thirds of the validators are required, if any.
+    two_thirds_transition: Option<BlockNumber>,
+    validators: Vec<Address>,
+    /// Blocks that are not final yet, oldest first, with their signer.
+    unfinalized: VecDeque<(BlockNumHash, Address)>,
+    /// Number of queued blocks sealed by each validator.
+    sign_count: BTreeMap<Address, usize>,
+    finalized: Option<BlockNumHash>,
+    next_block: Option<BlockNumber>,
+}
+
+impl FinalityTracker {
+    /// Creates a tracker that requires a majority of the validators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a tracker that requires two-thirds of the validators from the given block onward.
+    pub fn with_two_thirds_transition(transition: BlockNumber) -> Self {
+        Self {
+            two_thirds_transition: Some(transition),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the threshold in effect at the given block.
+    pub fn threshold_at(&self, block: BlockNumber) -> FinalityThreshold {
+        if self
+            .two_thirds_transition
+            .is_some_and(|transition| block >= transition)
+        {
+            FinalityThreshold::TwoThirds
+        } else {
+            FinalityThreshold::Majority
+        }
+    }
+
+    /// Returns the latest finalized block, if any.
+    pub const fn finalized(&self) -> Option<BlockNumHash> {
+        self.finalized
+    }
+
+    /// Returns the blocks that are not final yet, oldest first, with their signer.
+    pub const fn unfinalized(&self) -> &VecDeque<(BlockNumHash, Address)> {
+        &self.unfinalized
+    }
+
+    /// Processes the next header, sealed by one of `validators`.
+    ///
+    /// `validators` is the set that must have sealed the header, for example as returned by
+    /// [`MultiValidatorSet::validators_for`](crate::validator_set::MultiValidatorSet::validators_for).
+    /// Returns the latest block that became final, if any.
+    pub fn on_header(
+        &mut self,
+        header: &GnosisHeader,
+        validators: &[Address],
+    ) -> Result<Option<BlockNumHash>, FinalityError> {
+        if let Some(expected) = self.next_block
+            && header.number != expected
+        {
+            return Err(FinalityError::OutOfOrder {
+                expected,
+                got: header.number,
+            });
+        }
+        let signer = aura::recover_seal_signer(header).map_err(FinalityError::Seal)?;
+        if !validators.contains(&signer) {
+            return Err(FinalityError::UnknownSigner {
+                block: header.number,
+                signer,
+            });
+        }
+        self.next_block = Some(header.number + 1);
+
+        if self.validators != validators {
+            self.validators = validators.to_vec();
+            self.unfinalized.clear();
+            self.sign_count.clear();
+        }
+        self.unfinalized
+            .push_back((BlockNumHash::new(header.number, header.hash_slow()), signer));
+        *self.sign_count.entry(signer).or_default() += 1;
+
+        let threshold = self.threshold_at(header.number);
+        let mut newly_finalized = None;
+        while threshold.is_reached(self.sign_count.len(), self.validators.len()) {
+            let Some((block, signer)) = self.unfinalized.pop_front() else {
+                break;
+            };
+            if let Some(count) = self.sign_count.get_mut(&signer) {
+                *count -= 1;
+                if *count == 0 {
+                    self.sign_count.remove(&signer);
+                }
+            }
+            newly_finalized = Some(block);
+        }
+        if newly_finalized.is_some() {
+            self.finalized = newly_finalized;
+        }
+        Ok(newly_finalized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aura::{SigningKey, seal_header};
+    use alloy_primitives::{B256, FixedBytes, U256};
+
+    fn keys(count: u8) -> Vec<SigningKey> {
+        (1..=count)
+            .map(|byte| SigningKey::from_slice(&[byte; 32]).unwrap())
+            .collect()
+    }
+
+    fn addresses(keys: &[SigningKey]) -> Vec<Address> {
+        keys.iter().map(Address::from_private_key).collect()
+    }
+
+    fn sealed_header(number: BlockNumber, key: &SigningKey) -> GnosisHeader {
+        let header = GnosisHeader {
+            number,
+            parent_hash: B256::with_last_byte(number as u8),
+            ..Default::default()
+        };
+        seal_header(header, U256::from(number), key).unwrap()
+    }
+
+    #[test]
+    fn test_threshold() {
+        assert!(!FinalityThreshold::Majority.is_reached(2, 4));
+        assert!(FinalityThreshold::Majority.is_reached(3, 4));
+        assert!(!FinalityThreshold::TwoThirds.is_reached(2, 3));
+        assert!(FinalityThreshold::TwoThirds.is_reached(3, 4));
+
+        let tracker = FinalityTracker::with_two_thirds_transition(10);
+        assert_eq!(tracker.threshold_at(9), FinalityThreshold::Majority);
+        assert_eq!(tracker.threshold_at(10), FinalityThreshold::TwoThirds);
+    }
+
+    #[test]
+    fn test_majority_finality() {
+        let keys = keys(3);
+        let validators = addresses(&keys);
+        let mut tracker = FinalityTracker::new();
+
+        let first = sealed_header(1, &keys[1]);
+        assert_eq!(tracker.on_header(&first, &validators), Ok(None));
+        assert_eq!(tracker.finalized(), None);
+
+        // A second validator building on top finalizes the first block
+        let second = sealed_header(2, &keys[2]);
+        let finalized = tracker.on_header(&second, &validators).unwrap();
+        assert_eq!(finalized, Some(BlockNumHash::new(1, first.hash_slow())));
+        assert_eq!(tracker.finalized(), finalized);
+        assert_eq!(tracker.unfinalized().len(), 1);
+
+        let third = sealed_header(3, &keys[0]);
+        assert_eq!(
+            tracker.on_header(&third, &validators),
+            Ok(Some(BlockNumHash::new(2, second.hash_slow())))
+        );
+    }
+
+    #[test]
+    fn test_two_thirds_finality() {
+        let keys = keys(3);
+        let validators = addresses(&keys);
+        let mut tracker = FinalityTracker::with_two_thirds_transition(0);
+
+        assert_eq!(
+            tracker.on_header(&sealed_header(1, &keys[1]), &validators),
+            Ok(None)
+        );
+        assert_eq!(
+            tracker.on_header(&sealed_header(2, &keys[2]), &validators),
+            Ok(None)
+        );
+        let finalized = tracker
+            .on_header(&sealed_header(3, &keys[0]), &validators)
+            .unwrap();
+        assert_eq!(finalized.map(|block| block.number), Some(1));
+    }
+
+    #[test]
+    fn test_single_validator_finalizes_immediately() {
+        let keys = keys(1);
+        let mut tracker = FinalityTracker::new();
+        let header = sealed_header(5, &keys[0]);
+        assert_eq!(
+            tracker.on_header(&header, &addresses(&keys)),
+            Ok(Some(BlockNumHash::new(5, header.hash_slow())))
+        );
+    }
+
+    #[test]
+    fn test_validator_set_change_resets_queue() {
+        let keys = keys(4);
+        let mut tracker = FinalityTracker::new();
+        let old_set = addresses(&keys[..3]);
+        tracker
+            .on_header(&sealed_header(1, &keys[1]), &old_set)
+            .unwrap();
+
+        let new_set = addresses(&keys[1..]);
+        assert_eq!(
+            tracker.on_header(&sealed_header(2, &keys[2]), &new_set),
+            Ok(None)
+        );
+        assert_eq!(tracker.unfinalized().len(), 1);
+    }
+
+    #[test]
+    fn test_errors() {
+        let keys = keys(3);
+        let validators = addresses(&keys[..2]);
+        let mut tracker = FinalityTracker::new();
+
+        let header = sealed_header(1, &keys[2]);
+        assert_eq!(
+            tracker.on_header(&header, &validators),
+            Err(FinalityError::UnknownSigner {
+                block: 1,
+                signer: Address::from_private_key(&keys[2])
+            })
+        );
+
+        tracker
+            .on_header(&sealed_header(1, &keys[1]), &validators)
+            .unwrap();
+        assert_eq!(
+            tracker.on_header(&sealed_header(3, &keys[1]), &validators),
+            Err(FinalityError::OutOfOrder {
+                expected: 2,
+                got: 3
+            })
+        );
+
+        let mut unsealed = sealed_header(2, &keys[0]);
+        unsealed.aura_seal = Some(FixedBytes::ZERO);
+        assert_eq!(
+            tracker.on_header(&unsealed, &validators),
+            Err(FinalityError::Seal(SealError::InvalidSignature))
+        );
+    }
+}
@@ -0,0 +1,356 @@
+pub use alloy_eip2124::{ForkFilter, ForkFilterKey, ForkHash, ForkId, Head, ValidationError};
+use alloy_genesis::ChainConfig;
+use alloy_primitives::{B256, BlockNumber, b256};
+
+use crate::header::GnosisHeader;
+
+/// Hash of the Gnosis Chain mainnet genesis block.
+pub const GNOSIS_GENESIS_HASH: B256 =
+    b256!("4f1dd23188aab3a76b463e4af801b52b1248ef073c648cbdc4c9333d3da79756");
+
+/// Hash of the Chiado testnet genesis block.
+pub const CHIADO_GENESIS_HASH: B256 =
+    b256!("ada44fd8d2ecab8b08f256af07ad3e777f17fb434f8f8e678b312f576212ba9a");
+
+/// Genesis and hardfork activations a Gnosis chain advertises in its EIP-2124 fork identifier.
+///
+/// Block-activated forks come before timestamp-activated ones, as required by EIP-6122. Forks
+/// activated at genesis and timestamp forks activated before the genesis block are not part of
+/// the identifier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForkSchedule {
+    /// Hash of the genesis block.
+    pub genesis_hash: B256,
+    /// Timestamp of the genesis block.
+    pub genesis_timestamp: u64,
+    /// Activation of every fork, in any order.
+    pub forks: Vec<ForkFilterKey>,
+}
+
+impl ForkSchedule {
+    /// Creates a schedule for the given genesis header and fork activations.
+    pub fn new(genesis: &GnosisHeader, forks: impl IntoIterator<Item = ForkFilterKey>) -> Self {
+        Self {
+            genesis_hash: genesis.hash_slow(),
+            genesis_timestamp: genesis.timestamp,
+            forks: forks.into_iter().collect(),
+        }
+    }
+
+    /// Returns the fork schedule of Gnosis Chain mainnet.
+    ///
+    /// Besides the Ethereum hardforks, Gnosis advertises the activation of POSDAO.
+    pub fn gnosis() -> Self {
+        Self {
+            genesis_hash: GNOSIS_GENESIS_HASH,
+            genesis_timestamp: 0,
+            forks: vec![
+                // Constantinople
+                ForkFilterKey::Block(1_604_400),
+                // Petersburg
+                ForkFilterKey::Block(2_508_800),
+                // Istanbul
+                ForkFilterKey::Block(7_298_030),
+                // POSDAO
+                ForkFilterKey::Block(9_186_425),
+                // Berlin
+                ForkFilterKey::Block(16_101_500),
+                // London
+                ForkFilterKey::Block(19_040_000),
+                // Shanghai
+                ForkFilterKey::Time(1_690_889_660),
+                // Cancun
+                ForkFilterKey::Time(1_710_181_820),
+                // Prague
+                ForkFilterKey::Time(1_746_021_820),
+            ],
+        }
+    }
+
+    /// Returns the fork schedule of the Chiado testnet.
+    ///
+    /// Every block-activated fork is active from genesis.
+    pub fn chiado() -> Self {
+        Self {
+            genesis_hash: CHIADO_GENESIS_HASH,
+            genesis_timestamp: 0,
+            forks: vec![
+                // Shanghai
+                ForkFilterKey::Time(1_684_934_220),
+                // Cancun
+                ForkFilterKey::Time(1_706_724_940),
+                // Prague
+                ForkFilterKey::Time(1_741_254_220),
+            ],
+        }
+    }
+
+    /// Returns the fork schedule for a known Gnosis chain id.
+    pub fn for_chain(chain_id: u64) -> Option<Self> {
+        match chain_id {
+            100 => Some(Self::gnosis()),
+            10200 => Some(Self::chiado()),
+            _ => None,
+        }
+    }
+
+    /// Builds the schedule of a custom chain from its chain spec config and genesis header.
+    ///
+    /// Every block and timestamp activation of the config is included. Gnosis-specific
+    /// transitions such as POSDAO have no field in the config and have to be added to
+    /// [`ForkSchedule::forks`] separately.
+    pub fn from_chain_config(config: &ChainConfig, genesis: &GnosisHeader) -> Self {
+        let blocks = [
+            config.homestead_block,
+            config.dao_fork_block,
+            config.eip150_block,
+            config.eip155_block,
+            config.eip158_block,
+            config.byzantium_block,
+            config.constantinople_block,
+            config.petersburg_block,
+            config.istanbul_block,
+            config.muir_glacier_block,
+            config.berlin_block,
+            config.london_block,
+            config.arrow_glacier_block,
+            config.gray_glacier_block,
+            config.merge_netsplit_block,
+        ];
+        let timestamps = [
+            config.shanghai_time,
+            config.cancun_time,
+            config.prague_time,
+            config.osaka_time,
+            config.bpo1_time,
+            config.bpo2_time,
+            config.bpo3_time,
+            config.bpo4_time,
+            config.bpo5_time,
+        ];
+        Self::new(
+            genesis,
+            blocks
+                .into_iter()
+                .flatten()
+                .map(ForkFilterKey::Block)
+                .chain(timestamps.into_iter().flatten().map(ForkFilterKey::Time)),
+        )
+    }
+
+    /// Returns a filter for validating remote fork identifiers against the given local head.
+    pub fn fork_filter(&self, head: Head) -> ForkFilter {
+        ForkFilter::new(
+            head,
+            self.genesis_hash,
+            self.genesis_timestamp,
+            self.forks.iter().copied(),
+        )
+    }
+
+    /// Returns the fork identifier advertised at the given head block number and timestamp.
+    pub fn fork_id(&self, number: BlockNumber, timestamp: u64) -> ForkId {
+        self.fork_filter(Self::head(number, timestamp)).current()
+    }
+
+    /// Returns the fork identifier advertised with `header` as head.
+    pub fn fork_id_at(&self, header: &GnosisHeader) -> ForkId {
+        self.fork_id(header.number, header.timestamp)
+    }
+
+    /// Checks the fork identifier of a remote peer against the local head, following the rules
+    /// of EIP-2124.
+    pub fn validate(
+        &self,
+        number: BlockNumber,
+        timestamp: u64,
+        remote: ForkId,
+    ) -> Result<(), ValidationError> {
+        self.fork_filter(Self::head(number, timestamp))
+            .validate(remote)
+    }
+
+    fn head(number: BlockNumber, timestamp: u64) -> Head {
+        Head {
+            number,
+            timestamp,
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B64;
+
+    fn fork_id(hash: [u8; 4], next: u64) -> ForkId {
+        ForkId {
+            hash: ForkHash(hash),
+            next,
+        }
+    }
+
+    #[test]
+    fn test_gnosis_fork_ids() {
+        // Fork identifiers advertised by Nethermind and Erigon
+        let schedule = ForkSchedule::gnosis();
+        let cases = [
+            (0, 0, [0xf6, 0x49, 0x09, 0xb1], 1_604_400),
+            (1_604_399, 0, [0xf6, 0x49, 0x09, 0xb1], 1_604_400),
+            (1_604_400, 0, [0xfd, 0xe2, 0xd0, 0x83], 2_508_800),
+            (2_508_800, 0, [0xfc, 0x1d, 0x8f, 0x2f], 7_298_030),
+            (7_298_030, 0, [0x54, 0xd0, 0x5e, 0x6c], 9_186_425),
+            (9_186_425, 0, [0xb6, 0xe6, 0xcd, 0x81], 16_101_500),
+            (16_101_500, 0, [0x06, 0x9a, 0x83, 0xd9], 19_040_000),
+            (19_040_000, 0, [0x01, 0x84, 0x79, 0xd3], 1_690_889_660),
+            (
+                30_000_000,
+                1_690_889_655,
+                [0x01, 0x84, 0x79, 0xd3],
+                1_690_889_660,
+            ),
+            (
+                30_000_001,
+                1_690_889_660,
+                [0x2e, 0xfe, 0x91, 0xba],
+                1_710_181_820,
+            ),
+            (
+                32_000_000,
+                1_710_181_820,
+                [0x13, 0x84, 0xdf, 0xc1],
+                1_746_021_820,
+            ),
+            (40_000_000, 1_746_021_820, [0x2f, 0x09, 0x5d, 0x4a], 0),
+        ];
+        for (number, timestamp, hash, next) in cases {
+            assert_eq!(
+                schedule.fork_id(number, timestamp),
+                fork_id(hash, next),
+                "block {number}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_chiado_fork_ids() {
+        let schedule = ForkSchedule::chiado();
+        let cases = [
+            (0, 0, [0x50, 0xd3, 0x9d, 0x7b], 1_684_934_220),
+            (
+                4_100_418,
+                1_684_934_219,
+                [0x50, 0xd3, 0x9d, 0x7b],
+                1_684_934_220,
+            ),
+            (
+                4_100_419,
+                1_684_934_220,
+                [0xa1, 0x5a, 0x42, 0x52],
+                1_706_724_940,
+            ),
+            (
+                8_021_278,
+                1_706_724_940,
+                [0x5f, 0xbc, 0x16, 0xbc],
+                1_741_254_220,
+            ),
+            (15_000_000, 1_741_254_220, [0x8b, 0xa5, 0x17, 0x86], 0),
+        ];
+        for (number, timestamp, hash, next) in cases {
+            assert_eq!(
+                schedule.fork_id(number, timestamp),
+                fork_id(hash, next),
+                "block {number}"
+            );
+        }
+        assert_eq!(ForkSchedule::for_chain(10200), Some(schedule));
+        assert_eq!(ForkSchedule::for_chain(1), None);
+    }
+
+    #[test]
+    fn test_validate_remote_fork_ids() {
+        let schedule = ForkSchedule::gnosis();
+        // Local head is past Cancun, before Prague
+        let (number, timestamp) = (35_000_000, 1_720_000_000);
+        let validate = |remote| schedule.validate(number, timestamp, remote);
+
+        // Same fork, with or without Prague announced
+        assert_eq!(
+            validate(fork_id([0x13, 0x84, 0xdf, 0xc1], 1_746_021_820)),
+            Ok(())
+        );
+        assert_eq!(validate(fork_id([0x13, 0x84, 0xdf, 0xc1], 0)), Ok(()));
+        // Remote is behind but knows about Cancun
+        assert_eq!(
+            validate(fork_id([0x2e, 0xfe, 0x91, 0xba], 1_710_181_820)),
+            Ok(())
+        );
+        // Remote already passed Prague
+        assert_eq!(validate(fork_id([0x2f, 0x09, 0x5d, 0x4a], 0)), Ok(()));
+
+        // Remote is behind and does not know about Cancun
+        let stale = fork_id([0x2e, 0xfe, 0x91, 0xba], 0);
+        assert!(matches!(
+            validate(stale),
+            Err(ValidationError::RemoteStale { .. })
+        ));
+        // Remote is on Chiado
+        let chiado = ForkSchedule::chiado().fork_id(number, timestamp);
+        assert!(matches!(
+            validate(chiado),
+            Err(ValidationError::LocalIncompatibleOrStale { .. })
+        ));
+        // Remote announces a fork the local head already passed without activating it
+        let missed = fork_id([0x13, 0x84, 0xdf, 0xc1], 1_715_000_000);
+        assert!(matches!(
+            validate(missed),
+            Err(ValidationError::LocalIncompatibleOrStale { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_chain_config() {
+        let genesis = GnosisHeader {
+            timestamp: 1_000,
+            mix_hash: Some(B256::ZERO),
+            nonce: Some(B64::ZERO),
+            ..Default::default()
+        };
+        let config = ChainConfig {
+            chain_id: 1337,
+            homestead_block: Some(0),
+            london_block: Some(5),
+            // Before genesis, so not part of the identifier
+            shanghai_time: Some(500),
+            cancun_time: Some(2_000),
+            ..Default::default()
+        };
+        let schedule = ForkSchedule::from_chain_config(&config, &genesis);
+        assert_eq!(schedule.genesis_hash, genesis.hash_slow());
+
+        let genesis_hash = ForkHash::from(genesis.hash_slow());
+        assert_eq!(
+            schedule.fork_id_at(&genesis),
+            ForkId {
+                hash: genesis_hash,
+                next: 5
+            }
+        );
+        assert_eq!(
+            schedule.fork_id(5, 1_500),
+            ForkId {
+                hash: genesis_hash + 5u64,
+                next: 2_000
+            }
+        );
+        assert_eq!(
+            schedule.fork_id(6, 2_000),
+            ForkId {
+                hash: genesis_hash + 5u64 + 2_000u64,
+                next: 0
+            }
+        );
+    }
+}
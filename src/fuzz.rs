@@ -0,0 +1,161 @@
+//! Fuzz targets for the decoders of [`GnosisHeader`], run with `cargo test-fuzz`.
+//!
+//! Each target must not panic on any input, and every header it decodes must survive an
+//! encode-decode round trip unchanged. The tests record the seed corpus.
+
+use alloy_rlp::{Decodable, Encodable};
+use reth_codecs::Compact;
+use reth_db::table::{Compress, Decompress};
+
+use crate::header::GnosisHeader;
+
+#[test_fuzz::test_fuzz]
+fn rlp_decode(data: Vec<u8>) {
+    if let Ok(header) = GnosisHeader::decode(&mut data.as_slice()) {
+        let encoded = alloy_rlp::encode(&header);
+        assert_eq!(encoded.len(), header.length());
+        assert_eq!(encoded, data[..encoded.len()]);
+        assert_eq!(
+            GnosisHeader::decode(&mut encoded.as_slice()).as_ref(),
+            Ok(&header)
+        );
+    }
+    if let Ok(header) = GnosisHeader::decode_forward_compatible(&mut data.as_slice()) {
+        let encoded = alloy_rlp::encode(&header);
+        assert_eq!(encoded, data[..encoded.len()]);
+        let _ = header.hash_slow();
+        let _ = header.aura_seal_hash();
+    }
+}
+
+#[test_fuzz::test_fuzz]
+fn compact_decode(data: Vec<u8>) {
+    if let Some((header, rest)) = GnosisHeader::try_from_compact(&data, data.len()) {
+        assert!(rest.is_empty());
+        let mut buf = Vec::new();
+        let len = header.to_compact(&mut buf);
+        assert_eq!(GnosisHeader::from_compact(&buf, len).0, header);
+        let _ = header.hash_slow();
+    }
+}
+
+#[test_fuzz::test_fuzz]
+fn decompress(data: Vec<u8>) {
+    if let Ok(header) = GnosisHeader::decompress(&data) {
+        let compressed = header.clone().compress();
+        assert_eq!(GnosisHeader::decompress(&compressed).as_ref(), Ok(&header));
+        let _ = header.hash_slow();
+    }
+}
+
+#[test_fuzz::test_fuzz]
+fn json_decode(data: Vec<u8>) {
+    if let Ok(header) = serde_json::from_slice::<GnosisHeader>(&data) {
+        let json = serde_json::to_vec(&header).unwrap();
+        let decoded = serde_json::from_slice::<GnosisHeader>(&json).unwrap();
+        assert_eq!(decoded, header);
+        let _ = header.hash_slow();
+    }
+}
+
+mod tests {
+    use super::*;
+    use crate::{
+        fork_id::GNOSIS_GENESIS_HASH,
+        test_utils::{TestChainConfig, TestChainGenerator},
+    };
+    use alloy_consensus::EMPTY_OMMER_ROOT_HASH;
+    use alloy_primitives::{B256, Bytes, FixedBytes, U256, b256};
+    use alloy_trie::EMPTY_ROOT_HASH;
+
+    /// The Gnosis Chain mainnet genesis header, checked against its known hash.
+    fn gnosis_genesis() -> GnosisHeader {
+        let header = GnosisHeader {
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            state_root: b256!("40cf4430ecaa733787d1a65154a3b9efb560c95d9e324a23b97f0609b539133b"),
+            transactions_root: EMPTY_ROOT_HASH,
+            receipts_root: EMPTY_ROOT_HASH,
+            difficulty: U256::from(0x20000),
+            gas_limit: 0x989680,
+            aura_step: Some(U256::ZERO),
+            aura_seal: Some(FixedBytes::ZERO),
+            ..Default::default()
+        };
+        assert_eq!(header.hash_slow(), GNOSIS_GENESIS_HASH);
+        header
+    }
+
+    /// The Gnosis genesis, and headers spanning AuRa, the merge, Shanghai, Cancun and Prague on
+    /// both test chains, the last one with fields from unknown forks.
+    fn seed_headers() -> Vec<GnosisHeader> {
+        let mut headers = vec![gnosis_genesis()];
+        for chain_id in [100, 10200] {
+            let mut generator = TestChainGenerator::new(TestChainConfig {
+                chain_id,
+                seed: chain_id,
+                ..Default::default()
+            });
+            headers.push(generator.genesis().clone());
+            headers.extend(generator.headers(20));
+        }
+        let mut extended = headers.last().unwrap().clone();
+        extended.block_access_list_hash = Some(B256::repeat_byte(0x11));
+        extended.trailing_fields = vec![Bytes::from_static(&[0x80])];
+        headers.push(extended);
+        headers
+    }
+
+    /// Every truncation of `data`, and every copy of it with one byte flipped.
+    fn corrupted(data: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+        let truncated = (0..data.len()).map(|len| data[..len].to_vec());
+        let flipped = (0..data.len()).map(|index| {
+            let mut corrupted = data.to_vec();
+            corrupted[index] ^= 0xff;
+            corrupted
+        });
+        truncated.chain(flipped)
+    }
+
+    #[test]
+    fn test_rlp_decode_seeds() {
+        for header in seed_headers() {
+            let encoded = alloy_rlp::encode(&header);
+            rlp_decode(encoded.clone());
+            corrupted(&encoded).for_each(rlp_decode);
+        }
+    }
+
+    #[test]
+    fn test_compact_decode_seeds() {
+        for header in seed_headers() {
+            let mut buf = Vec::new();
+            header.to_compact(&mut buf);
+            compact_decode(buf.clone());
+            corrupted(&buf).for_each(compact_decode);
+        }
+    }
+
+    #[test]
+    fn test_decompress_seeds() {
+        for header in seed_headers() {
+            let compressed = header.compress();
+            decompress(compressed.clone());
+            corrupted(&compressed).for_each(decompress);
+        }
+    }
+
+    #[test]
+    fn test_json_decode_seeds() {
+        for header in seed_headers() {
+            let json = serde_json::to_vec(&header).unwrap();
+            json_decode(json.clone());
+
+            for len in (0..json.len()).step_by(7) {
+                json_decode(json[..len].to_vec());
+            }
+        }
+
+        // Neither seal variant, which gets the default mix hash and nonce
+        json_decode(br#"{"parentHash":"0x0000000000000000000000000000000000000000000000000000000000000000","sha3Uncles":"0x0000000000000000000000000000000000000000000000000000000000000000","miner":"0x0000000000000000000000000000000000000000","stateRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","receiptsRoot":"0x0000000000000000000000000000000000000000000000000000000000000000","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","difficulty":"0x0","number":"0x1","gasLimit":"0x1","gasUsed":"0x0","timestamp":"0x0","extraData":"0x","mixHash":null,"nonce":null}"#.to_vec());
+    }
+}
@@ -20,6 +20,8 @@ use reth_primitives_traits::InMemorySize;
 use reth_tracing::tracing::debug;
 use serde::{Deserialize, Serialize};
 
+use crate::blob_schedule::BlobSchedule;
+
 pub fn default_mix_hash() -> Option<B256> {
     Some(B256::ZERO)
 }
@@ -44,7 +46,7 @@ pub fn default_nonce() -> Option<B64> {
     Serialize,
     Deserialize,
 )]
-#[serde(rename_all = "camelCase")]
+#[serde(rename_all = "camelCase", remote = "Self")]
 pub struct GnosisHeader {
     /// The Keccak 256-bit hash of the parent
     /// block’s header, in its entirety; formally Hp.
@@ -92,11 +94,11 @@ pub struct GnosisHeader {
     /// A 256-bit hash which, combined with the
     /// nonce, proves that a sufficient amount of computation has been carried out on this block;
     /// formally Hm.
-    #[serde(default = "default_mix_hash", skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub mix_hash: Option<B256>,
     /// A 64-bit value which, combined with the mixhash, proves that a sufficient amount of
     /// computation has been carried out on this block; formally Hn.
-    #[serde(default = "default_nonce", skip_serializing_if = "Option::is_none")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nonce: Option<B64>,
     /// Gnosis-specific fields for Aura Consensus
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -151,6 +153,48 @@ pub struct GnosisHeader {
     /// [EIP-7685]: https://eips.ethereum.org/EIPS/eip-7685
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub requests_hash: Option<B256>,
+    /// The Keccak 256-bit hash of the RLP encoded block-level access list, as proposed by
+    /// [EIP-7928].
+    ///
+    /// [EIP-7928]: https://eips.ethereum.org/EIPS/eip-7928
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_access_list_hash: Option<B256>,
+    /// RLP items that follow the last field known to this crate, kept verbatim. An item in the
+    /// position of the block access list hash that is not a 32-byte string is kept here too.
+    ///
+    /// Only populated by [`GnosisHeader::decode_forward_compatible`]. They are re-emitted by
+    /// [`Encodable::encode`] so that [`GnosisHeader::hash_slow`] still matches the block hash of
+    /// headers produced by a fork this crate does not know about yet.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trailing_fields: Vec<Bytes>,
+}
+
+impl Serialize for GnosisHeader {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Self::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for GnosisHeader {
+    /// Deserializes a header, defaulting a missing mix hash and nonce to zero.
+    ///
+    /// The defaults are not applied to headers carrying AuRa seal fields: JSON of a pre-merge
+    /// header has no mix hash or nonce, and defaulting them would make it encode as a post-merge
+    /// header. A mix hash or nonce present in the JSON is always kept. Headers left without a
+    /// complete seal variant, such as an AuRa step without a seal, are rejected.
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut header = Self::deserialize(deserializer)?;
+        if header.aura_step.is_none() && header.aura_seal.is_none() {
+            header.mix_hash = header.mix_hash.or_else(default_mix_hash);
+            header.nonce = header.nonce.or_else(default_nonce);
+        }
+        if !header.has_complete_seal() {
+            return Err(serde::de::Error::custom(
+                "header has neither a mix hash and nonce nor an AuRa step and seal",
+            ));
+        }
+        Ok(header)
+    }
 }
 
 /// Bincode-compatible [`Header`] serde implementation.
@@ -212,6 +256,18 @@ pub mod serde_bincode_compat {
         #[serde(default)]
         requests_hash: Option<B256>,
         extra_data: Cow<'a, Bytes>,
+        #[serde(default)]
+        extension: Option<GnosisHeaderExt<'a>>,
+    }
+
+    /// Fields added after the layout of [`GnosisHeader`] was fixed.
+    ///
+    /// Bincode is positional, so new fields are appended here rather than inserted between the
+    /// existing ones.
+    #[derive(Debug, Default, Serialize, Deserialize)]
+    struct GnosisHeaderExt<'a> {
+        block_access_list_hash: Option<B256>,
+        trailing_fields: Cow<'a, [Bytes]>,
     }
 
     impl<'a> From<&'a super::GnosisHeader> for GnosisHeader<'a> {
@@ -240,12 +296,18 @@ pub mod serde_bincode_compat {
                 parent_beacon_block_root: value.parent_beacon_block_root,
                 requests_hash: value.requests_hash,
                 extra_data: Cow::Borrowed(&value.extra_data),
+                extension: (value.block_access_list_hash.is_some() || value.has_trailing_fields())
+                    .then(|| GnosisHeaderExt {
+                        block_access_list_hash: value.block_access_list_hash,
+                        trailing_fields: Cow::Borrowed(&value.trailing_fields),
+                    }),
             }
         }
     }
 
     impl<'a> From<GnosisHeader<'a>> for super::GnosisHeader {
         fn from(value: GnosisHeader<'a>) -> Self {
+            let extension = value.extension.unwrap_or_default();
             Self {
                 parent_hash: value.parent_hash,
                 ommers_hash: value.ommers_hash,
@@ -269,6 +331,8 @@ pub mod serde_bincode_compat {
                 excess_blob_gas: value.excess_blob_gas,
                 parent_beacon_block_root: value.parent_beacon_block_root,
                 requests_hash: value.requests_hash,
+                block_access_list_hash: extension.block_access_list_hash,
+                trailing_fields: extension.trailing_fields.into_owned(),
                 extra_data: value.extra_data.into_owned(),
             }
         }
@@ -288,7 +352,13 @@ pub mod serde_bincode_compat {
         where
             D: Deserializer<'de>,
         {
-            GnosisHeader::deserialize(deserializer).map(Into::into)
+            let header: super::GnosisHeader = GnosisHeader::deserialize(deserializer)?.into();
+            if !header.has_complete_seal() {
+                return Err(serde::de::Error::custom(
+                    "header has neither a mix hash and nonce nor an AuRa step and seal",
+                ));
+            }
+            Ok(header)
         }
     }
 
@@ -370,6 +440,50 @@ impl GnosisHeader {
         ))
     }
 
+    /// Returns the [`BlobParams`] in effect for _this_ block according to `schedule`.
+    ///
+    /// Returns `None` if the block predates Cancun or no schedule entry is active yet.
+    pub fn blob_params(&self, schedule: &BlobSchedule) -> Option<BlobParams> {
+        schedule.params_for_header(self)
+    }
+
+    /// Returns the blob fee for _this_ block, using the [`BlobParams`] resolved from `schedule`.
+    ///
+    /// See also [Self::blob_fee]
+    pub fn scheduled_blob_fee(&self, schedule: &BlobSchedule) -> Option<u128> {
+        self.blob_fee(self.blob_params(schedule)?)
+    }
+
+    /// Calculate excess blob gas for the next block, using the [`BlobParams`] the schedule
+    /// resolves for the next block's timestamp.
+    ///
+    /// Returns `None` if no excess blob gas is set or no schedule entry is active at
+    /// `next_timestamp`.
+    pub fn scheduled_next_block_excess_blob_gas(
+        &self,
+        schedule: &BlobSchedule,
+        next_timestamp: u64,
+    ) -> Option<u64> {
+        self.next_block_excess_blob_gas(schedule.params_at_timestamp(next_timestamp)?)
+    }
+
+    /// Returns the blob fee for the next block, using the [`BlobParams`] the schedule resolves
+    /// for the next block's timestamp.
+    ///
+    /// See also [Self::scheduled_next_block_excess_blob_gas]
+    pub fn scheduled_next_block_blob_fee(
+        &self,
+        schedule: &BlobSchedule,
+        next_timestamp: u64,
+    ) -> Option<u128> {
+        self.next_block_blob_fee(schedule.params_at_timestamp(next_timestamp)?)
+    }
+
+    /// Returns the maximum number of blobs allowed in _this_ block according to `schedule`.
+    pub fn max_blob_count(&self, schedule: &BlobSchedule) -> Option<u64> {
+        Some(self.blob_params(schedule)?.max_blob_count)
+    }
+
     /// Calculate a heuristic for the in-memory size of the [Header].
     #[inline]
     pub fn size_of(&self) -> usize {
@@ -393,10 +507,17 @@ impl GnosisHeader {
         mem::size_of::<Option<u128>>() + // excess blob gas
         mem::size_of::<Option<B256>>() + // parent beacon block root
         mem::size_of::<Option<B256>>() + // requests root
+        self.block_access_list_hash.map_or(0, |_| mem::size_of::<B256>()) + // block access list hash
+        self.trailing_fields.capacity() * mem::size_of::<Bytes>() + // trailing field handles
+        self.trailing_fields.iter().map(|field| field.len()).sum::<usize>() + // trailing fields
         self.extra_data.len() // extra data
     }
 
     fn header_payload_length(&self) -> usize {
+        self.payload_length(true)
+    }
+
+    fn payload_length(&self, with_seal: bool) -> usize {
         let mut length = 0;
         length += self.parent_hash.length();
         length += self.ommers_hash.length();
@@ -411,14 +532,9 @@ impl GnosisHeader {
         length += U256::from(self.gas_used).length();
         length += self.timestamp.length();
         length += self.extra_data.length();
-        if self.is_post_merge() {
-            // If the header is post-merge, we have mix_hash and nonce.
-            length += self.mix_hash.as_ref().map_or(0, |hash| hash.length());
-            length += self.nonce.as_ref().map_or(0, |nonce| nonce.length());
-        } else {
-            // If the header is pre-merge, we have aura_step and aura_seal.
-            length += self.aura_step.unwrap_or(U256::ZERO).length();
-            length += self.aura_seal.as_ref().map_or(0, |seal| seal.length());
+        // The seal fields are left out of the hash signed by AuRa authors.
+        if with_seal {
+            length += self.seal_fields().length();
         }
         // length += self.mix_hash.is_some().then(|| self.mix_hash.unwrap().length()).unwrap_or(0);
         // length += self.nonce.is_some().then(|| self.nonce.unwrap().length()).unwrap_or(0);
@@ -453,6 +569,17 @@ impl GnosisHeader {
             length += requests_hash.length();
         }
 
+        if let Some(block_access_list_hash) = self.block_access_list_hash {
+            length += block_access_list_hash.length();
+        }
+
+        // Trailing fields are stored as already encoded RLP items.
+        length += self
+            .trailing_fields
+            .iter()
+            .map(|field| field.len())
+            .sum::<usize>();
+
         length
     }
 
@@ -512,6 +639,13 @@ impl GnosisHeader {
         self.requests_hash.is_some()
     }
 
+    /// True if the Amsterdam hardfork is active.
+    ///
+    /// This function checks that the block access list hash is present.
+    pub const fn amsterdam_active(&self) -> bool {
+        self.block_access_list_hash.is_some()
+    }
+
     pub fn is_post_merge(&self) -> bool {
         self.mix_hash.is_some() && self.nonce.is_some()
     }
@@ -520,6 +654,42 @@ impl GnosisHeader {
         self.aura_step.is_some() && self.aura_seal.is_some()
     }
 
+    /// Returns `true` if the header has a seal variant to encode: a mix hash and nonce, or an AuRa
+    /// step and seal.
+    ///
+    /// Decoding and deserializing reject headers without one, and [`Encodable::encode`] panics on
+    /// them.
+    pub fn has_complete_seal(&self) -> bool {
+        self.is_post_merge() || self.is_pre_merge()
+    }
+
+    /// Returns the seal fields encoded after the extra data.
+    ///
+    /// The post-merge variant wins if both are set, as it always has.
+    ///
+    /// # Panics
+    ///
+    /// If the header has no complete seal variant, see [`GnosisHeader::has_complete_seal`].
+    fn seal_fields(&self) -> Seal<'_> {
+        match (
+            &self.mix_hash,
+            &self.nonce,
+            &self.aura_step,
+            &self.aura_seal,
+        ) {
+            (Some(mix_hash), Some(nonce), _, _) => Seal::PostMerge { mix_hash, nonce },
+            (_, _, Some(step), Some(seal)) => Seal::AuRa { step, seal },
+            _ => panic!(
+                "block {} has neither a mix hash and nonce nor an AuRa step and seal",
+                self.number
+            ),
+        }
+    }
+
+    /// Converts to an [`alloy_consensus::Header`].
+    ///
+    /// Fields introduced after Prague, such as the block access list hash, have no counterpart
+    /// in the alloy header and are dropped.
     pub fn to_alloy_header(&self) -> Header {
         if self.mix_hash.is_none() || self.nonce.is_none() {
             panic!(
@@ -579,6 +749,8 @@ impl From<Header> for GnosisHeader {
             excess_blob_gas: inner.excess_blob_gas,
             parent_beacon_block_root: inner.parent_beacon_block_root,
             requests_hash: inner.requests_hash,
+            block_access_list_hash: None,
+            trailing_fields: Vec::new(),
         }
     }
 }
@@ -725,6 +897,39 @@ impl alloy_consensus::BlockHeader for GnosisHeader {
 
 impl reth_primitives_traits::BlockHeader for GnosisHeader {}
 
+/// The seal fields of a header, between the extra data and the fork specific fields.
+enum Seal<'a> {
+    /// Mix hash and nonce of a post-merge header.
+    PostMerge { mix_hash: &'a B256, nonce: &'a B64 },
+    /// Step and signature of a header sealed by AuRa.
+    AuRa {
+        step: &'a U256,
+        seal: &'a FixedBytes<65>,
+    },
+}
+
+impl Encodable for Seal<'_> {
+    fn encode(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::PostMerge { mix_hash, nonce } => {
+                mix_hash.encode(out);
+                nonce.encode(out);
+            }
+            Self::AuRa { step, seal } => {
+                step.encode(out);
+                seal.encode(out);
+            }
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            Self::PostMerge { mix_hash, nonce } => mix_hash.length() + nonce.length(),
+            Self::AuRa { step, seal } => step.length() + seal.length(),
+        }
+    }
+}
+
 impl InMemorySize for GnosisHeader {
     fn size(&self) -> usize {
         let mut size = self.size_of();
@@ -741,11 +946,35 @@ impl InMemorySize for GnosisHeader {
 
 impl Encodable for GnosisHeader {
     fn encode(&self, out: &mut dyn BufMut) {
+        self.encode_fields(out, true);
+    }
+
+    fn length(&self) -> usize {
+        let mut length = 0;
+        length += self.header_payload_length();
+        length += length_of_length(length);
+        length
+    }
+}
+
+impl GnosisHeader {
+    /// Hash of the header without its seal fields.
+    ///
+    /// For pre-merge headers this is the message signed into `aura_seal` by the block author,
+    /// called the "bare hash" by OpenEthereum. Fields following the seal, such as the base fee,
+    /// are still included.
+    pub fn aura_seal_hash(&self) -> B256 {
+        let mut out = Vec::<u8>::new();
+        self.encode_fields(&mut out, false);
+        keccak256(&out)
+    }
+
+    fn encode_fields(&self, out: &mut dyn BufMut, with_seal: bool) {
         let mut buffer = Vec::new();
 
         let list_header = alloy_rlp::Header {
             list: true,
-            payload_length: self.header_payload_length(),
+            payload_length: self.payload_length(with_seal),
         };
         list_header.encode(&mut buffer);
         self.parent_hash.encode(&mut buffer);
@@ -762,12 +991,8 @@ impl Encodable for GnosisHeader {
         self.timestamp.encode(&mut buffer);
         self.extra_data.encode(&mut buffer);
 
-        if self.is_post_merge() {
-            self.mix_hash.unwrap().encode(&mut buffer);
-            self.nonce.unwrap().encode(&mut buffer);
-        } else {
-            self.aura_step.unwrap().encode(&mut buffer);
-            self.aura_seal.as_ref().unwrap().encode(&mut buffer);
+        if with_seal {
+            self.seal_fields().encode(&mut buffer);
         }
 
         // Encode all the fork specific fields
@@ -795,20 +1020,43 @@ impl Encodable for GnosisHeader {
             requests_hash.encode(&mut buffer);
         }
 
+        if let Some(ref block_access_list_hash) = self.block_access_list_hash {
+            block_access_list_hash.encode(&mut buffer);
+        }
+
+        // Re-emit unknown fields verbatim so the header hash is preserved
+        for field in &self.trailing_fields {
+            buffer.put_slice(field);
+        }
+
         // Write the encoded buffer to the output
         out.put_slice(&buffer);
     }
-
-    fn length(&self) -> usize {
-        let mut length = 0;
-        length += self.header_payload_length();
-        length += length_of_length(length);
-        length
-    }
 }
 
 impl Decodable for GnosisHeader {
     fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Self::decode_fields(buf, false)
+    }
+}
+
+impl GnosisHeader {
+    /// Decodes a header, keeping any RLP items that follow the last known field.
+    ///
+    /// [`Decodable::decode`] rejects such headers with
+    /// [`alloy_rlp::Error::ListLengthMismatch`]. This variant stores the unrecognised items in
+    /// [`GnosisHeader::trailing_fields`] instead, so that binaries built before a fork that
+    /// appends header fields can keep following the chain.
+    pub fn decode_forward_compatible(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Self::decode_fields(buf, true)
+    }
+
+    /// Returns `true` if the header carries fields unknown to this crate.
+    pub fn has_trailing_fields(&self) -> bool {
+        !self.trailing_fields.is_empty()
+    }
+
+    fn decode_fields(buf: &mut &[u8], keep_trailing_fields: bool) -> alloy_rlp::Result<Self> {
         let rlp_head = alloy_rlp::Header::decode(buf)?;
         if !rlp_head.list {
             return Err(alloy_rlp::Error::UnexpectedString);
@@ -842,6 +1090,8 @@ impl Decodable for GnosisHeader {
             excess_blob_gas: None,
             parent_beacon_block_root: None,
             requests_hash: None,
+            block_access_list_hash: None,
+            trailing_fields: Vec::new(),
         };
 
         // Peek at the next RLP header without advancing buf
@@ -895,6 +1145,30 @@ impl Decodable for GnosisHeader {
             this.requests_hash = Some(B256::decode(buf)?);
         }
 
+        // Decode block access list hash. When keeping trailing fields, an item that cannot be
+        // one is left for them, since a fork may append a different field at this position.
+        if started_len - buf.len() < rlp_head.payload_length {
+            let next_head = alloy_rlp::Header::decode(&mut &buf[..])?;
+            if !keep_trailing_fields || (!next_head.list && next_head.payload_length == 32) {
+                this.block_access_list_hash = Some(B256::decode(buf)?);
+            }
+        }
+
+        // Capture any fields appended by forks we do not know about yet.
+        if keep_trailing_fields {
+            while started_len - buf.len() < rlp_head.payload_length {
+                let item = *buf;
+                let item_head = alloy_rlp::Header::decode(buf)?;
+                if item_head.payload_length > buf.len() {
+                    return Err(alloy_rlp::Error::InputTooShort);
+                }
+                *buf = &buf[item_head.payload_length..];
+                let item_len = item.len() - buf.len();
+                this.trailing_fields
+                    .push(Bytes::copy_from_slice(&item[..item_len]));
+            }
+        }
+
         let consumed = started_len - buf.len();
         if consumed != rlp_head.payload_length {
             return Err(alloy_rlp::Error::ListLengthMismatch {
@@ -933,6 +1207,189 @@ struct CompactHeader {
     extra_data: Bytes,
 }
 
+/// Header fields stored next to a [`CompactHeader`] whose flags have no room left for them.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize, Compact)]
+struct CompactHeaderExt {
+    block_access_list_hash: Option<B256>,
+    trailing_fields: Vec<Bytes>,
+}
+
+impl CompactHeader {
+    /// Decodes all of `buf` as written by the derived [`Compact::to_compact`], returning [`None`]
+    /// where the derived decoder would panic.
+    ///
+    /// The encoding starts with 32 bits of flags holding, in field order, the presence of the
+    /// withdrawals root, the byte lengths of the difficulty (6 bits), number, gas limit, gas used
+    /// and timestamp (4 bits each), and the presence of the remaining optional fields. The fields
+    /// follow in order: hashes, addresses and the bloom as is, integers big-endian without
+    /// leading zero bytes, optional hashes as is, other optional fields after their varuint
+    /// length, and the extra data until the end.
+    fn decode(buf: &[u8]) -> Option<Self> {
+        let mut reader = CompactReader(buf);
+        let mut flags = u32::from_le_bytes(reader.array()?);
+        let mut flag = |bits: u32| {
+            let value = flags & ((1 << bits) - 1);
+            flags >>= bits;
+            value as usize
+        };
+
+        let parent_hash = B256::from(reader.array()?);
+        let ommers_hash = B256::from(reader.array()?);
+        let beneficiary = Address::from(reader.array()?);
+        let state_root = B256::from(reader.array()?);
+        let transactions_root = B256::from(reader.array()?);
+        let receipts_root = B256::from(reader.array()?);
+        let withdrawals_root = reader.hash(flag(1))?;
+        let logs_bloom = Bloom::from(reader.array()?);
+        let difficulty = U256::from_be_bytes::<32>(reader.uint(flag(6))?);
+        let number = u64::from_be_bytes(reader.uint(flag(4))?);
+        let gas_limit = u64::from_be_bytes(reader.uint(flag(4))?);
+        let gas_used = u64::from_be_bytes(reader.uint(flag(4))?);
+        let timestamp = u64::from_be_bytes(reader.uint(flag(4))?);
+        let mix_hash = reader.hash(flag(1))?;
+        let nonce = reader.prefixed(flag(1), |value| be_uint(value).map(u64::from_be_bytes))?;
+        let aura_step = reader.prefixed(flag(1), |value| {
+            be_uint(value).map(U256::from_be_bytes::<32>)
+        })?;
+        let aura_seal = reader.prefixed(flag(1), |value| value.try_into().ok().map(FixedBytes))?;
+        let base_fee_per_gas =
+            reader.prefixed(flag(1), |value| be_uint(value).map(u64::from_be_bytes))?;
+        let blob_gas_used =
+            reader.prefixed(flag(1), |value| be_uint(value).map(u64::from_be_bytes))?;
+        let excess_blob_gas =
+            reader.prefixed(flag(1), |value| be_uint(value).map(u64::from_be_bytes))?;
+        let parent_beacon_block_root = reader.hash(flag(1))?;
+        let requests_hash = reader.hash(flag(1))?;
+
+        Some(Self {
+            parent_hash,
+            ommers_hash,
+            beneficiary,
+            state_root,
+            transactions_root,
+            receipts_root,
+            withdrawals_root,
+            logs_bloom,
+            difficulty,
+            number,
+            gas_limit,
+            gas_used,
+            timestamp,
+            mix_hash,
+            nonce,
+            aura_step,
+            aura_seal,
+            base_fee_per_gas,
+            blob_gas_used,
+            excess_blob_gas,
+            parent_beacon_block_root,
+            requests_hash,
+            extra_data: Bytes::copy_from_slice(reader.0),
+        })
+    }
+}
+
+impl CompactHeaderExt {
+    /// Converts into [`Some`] if any of the fields is set, otherwise returns [`None`].
+    fn into_option(self) -> Option<Self> {
+        (self.block_access_list_hash.is_some() || !self.trailing_fields.is_empty()).then_some(self)
+    }
+
+    /// Decodes all of `buf` as written by the derived [`Compact::to_compact`], returning [`None`]
+    /// where the derived decoder would panic.
+    ///
+    /// The encoding is a flag byte holding the presence of the block access list hash, the hash
+    /// if present, and the varuint count of trailing fields followed by each of them after its
+    /// varuint length.
+    fn decode(buf: &[u8]) -> Option<Self> {
+        let mut reader = CompactReader(buf);
+        let [flags] = reader.array()?;
+        let block_access_list_hash = reader.hash(usize::from(flags & 1))?;
+        let count = reader.varuint()?;
+        // Not preallocated, the count comes from the input
+        let mut trailing_fields = Vec::new();
+        for _ in 0..count {
+            let len = reader.varuint()?;
+            trailing_fields.push(Bytes::copy_from_slice(reader.take(len)?));
+        }
+        reader.0.is_empty().then_some(Self {
+            block_access_list_hash,
+            trailing_fields,
+        })
+    }
+}
+
+/// Reads the fields of a derived compact encoding, failing on input that is too short or
+/// declares out of range lengths.
+struct CompactReader<'a>(&'a [u8]);
+
+impl<'a> CompactReader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let (taken, rest) = self.0.split_at_checked(len)?;
+        self.0 = rest;
+        Some(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    /// Reads a big-endian integer of `len` bytes, left-padded to `N` bytes.
+    fn uint<const N: usize>(&mut self, len: usize) -> Option<[u8; N]> {
+        be_uint(self.take(len)?)
+    }
+
+    /// Reads a 32-byte hash if `present` is set.
+    fn hash(&mut self, present: usize) -> Option<Option<B256>> {
+        match present {
+            0 => Some(None),
+            _ => self.array().map(|hash| Some(B256::from(hash))),
+        }
+    }
+
+    /// Reads a field prefixed with its varuint length if `present` is set.
+    fn prefixed<T>(
+        &mut self,
+        present: usize,
+        decode: impl FnOnce(&[u8]) -> Option<T>,
+    ) -> Option<Option<T>> {
+        if present == 0 {
+            return Some(None);
+        }
+        let len = self.varuint()?;
+        decode(self.take(len)?).map(Some)
+    }
+
+    /// Reads a LEB128 varuint.
+    fn varuint(&mut self) -> Option<usize> {
+        let mut value = 0usize;
+        for shift in (0..usize::BITS).step_by(7) {
+            let [byte] = self.array()?;
+            value |= usize::from(byte & 0x7f).checked_shl(shift)?;
+            if byte < 0x80 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// Left-pads a big-endian integer to `N` bytes, failing if it is longer.
+fn be_uint<const N: usize>(value: &[u8]) -> Option<[u8; N]> {
+    let mut bytes = [0; N];
+    bytes[N.checked_sub(value.len())?..].copy_from_slice(value);
+    Some(bytes)
+}
+
+/// Prefix of a compact header that is followed by a [`CompactHeaderExt`].
+///
+/// The stored layout is the marker, the length of the [`CompactHeader`] as a big-endian `u32`, the
+/// [`CompactHeader`] and finally the [`CompactHeaderExt`]. Headers without extension fields are
+/// stored as a bare [`CompactHeader`], exactly as before the extension existed. No bare
+/// [`CompactHeader`] starts with the marker, since all-ones flags would give the difficulty a
+/// length of 63 bytes.
+const COMPACT_HEADER_EXT_MARKER: [u8; 4] = [0xff; 4];
+
 impl reth_codecs::Compact for GnosisHeader {
     fn to_compact<B>(&self, buf: &mut B) -> usize
     where
@@ -967,11 +1424,50 @@ impl reth_codecs::Compact for GnosisHeader {
             requests_hash: self.requests_hash,
             extra_data: self.extra_data.clone(),
         };
-        header.to_compact(buf)
+        let extension = CompactHeaderExt {
+            block_access_list_hash: self.block_access_list_hash,
+            trailing_fields: self.trailing_fields.clone(),
+        }
+        .into_option();
+        let Some(extension) = extension else {
+            return header.to_compact(buf);
+        };
+
+        let mut header_buf = Vec::new();
+        let header_len = header.to_compact(&mut header_buf);
+        buf.put_slice(&COMPACT_HEADER_EXT_MARKER);
+        buf.put_u32(header_len as u32);
+        buf.put_slice(&header_buf);
+        COMPACT_HEADER_EXT_MARKER.len() + 4 + header_len + extension.to_compact(buf)
     }
 
+    /// Panics if the first `len` bytes of `buf` are not a compact header with a complete seal,
+    /// since the trait has no way to report errors. Untrusted input goes through
+    /// [`GnosisHeader::try_from_compact`] instead.
     fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
-        let (header, _) = CompactHeader::from_compact(buf, len);
+        Self::try_from_compact(buf, len).expect("valid compact header")
+    }
+}
+
+impl GnosisHeader {
+    /// Decodes the first `len` bytes of `buf` like [`Compact::from_compact`], but returns [`None`]
+    /// if they are not a compact header with a complete seal.
+    ///
+    /// Every length read from the input is checked against it, so this never panics.
+    pub fn try_from_compact(buf: &[u8], len: usize) -> Option<(Self, &[u8])> {
+        let value = buf.get(..len)?;
+        let (header, extension) = match value.strip_prefix(&COMPACT_HEADER_EXT_MARKER) {
+            Some(rest) => {
+                let (header_len, rest) = rest.split_first_chunk::<4>()?;
+                let header_len = u32::from_be_bytes(*header_len) as usize;
+                let (header, extension) = rest.split_at_checked(header_len)?;
+                (
+                    CompactHeader::decode(header)?,
+                    CompactHeaderExt::decode(extension)?,
+                )
+            }
+            None => (CompactHeader::decode(value)?, CompactHeaderExt::default()),
+        };
         let alloy_header = Self {
             parent_hash: header.parent_hash,
             ommers_hash: header.ommers_hash,
@@ -999,9 +1495,13 @@ impl reth_codecs::Compact for GnosisHeader {
             excess_blob_gas: header.excess_blob_gas,
             parent_beacon_block_root: header.parent_beacon_block_root,
             requests_hash: header.requests_hash,
+            block_access_list_hash: extension.block_access_list_hash,
+            trailing_fields: extension.trailing_fields,
             extra_data: header.extra_data,
         };
-        (alloy_header, buf)
+        alloy_header
+            .has_complete_seal()
+            .then_some((alloy_header, &buf[len..]))
     }
 }
 
@@ -1015,8 +1515,9 @@ impl Compress for GnosisHeader {
 
 impl Decompress for GnosisHeader {
     fn decompress(value: &[u8]) -> Result<GnosisHeader, DatabaseError> {
-        let (obj, _) = Compact::from_compact(value, value.len());
-        Ok(obj)
+        Self::try_from_compact(value, value.len())
+            .map(|(obj, _)| obj)
+            .ok_or(DatabaseError::Decode)
     }
 }
 
@@ -1042,10 +1543,82 @@ impl HeaderMut for GnosisHeader {
     }
 }
 
+/// Generates headers with a consistent shape.
+///
+/// Every header has exactly one of the AuRa and post-merge seal variants, and its fork fields form
+/// a valid prefix: a fork field is only set if every earlier one is. AuRa headers stop at London,
+/// so they carry at most a base fee. AuRa steps fit into a `u64`, trailing fields are never
+/// generated, and extra data is at most 32 bytes.
+#[cfg(any(test, feature = "arbitrary"))]
+impl<'a> arbitrary::Arbitrary<'a> for GnosisHeader {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let extra_data_len = u.int_in_range(0..=32)?;
+        let mut header = Self {
+            parent_hash: u.arbitrary()?,
+            ommers_hash: u.arbitrary()?,
+            beneficiary: u.arbitrary()?,
+            state_root: u.arbitrary()?,
+            transactions_root: u.arbitrary()?,
+            receipts_root: u.arbitrary()?,
+            logs_bloom: u.arbitrary()?,
+            difficulty: u.arbitrary()?,
+            number: u.arbitrary()?,
+            gas_limit: u.arbitrary()?,
+            gas_used: u.arbitrary()?,
+            timestamp: u.arbitrary()?,
+            extra_data: Bytes::copy_from_slice(u.bytes(extra_data_len)?),
+            ..Default::default()
+        };
+
+        let max_forks = if u.arbitrary()? {
+            header.mix_hash = Some(u.arbitrary()?);
+            header.nonce = Some(u.arbitrary()?);
+            7
+        } else {
+            // Real steps are derived from timestamps. A step encoded to 32 bytes would be
+            // mistaken for a mix hash when decoding.
+            header.aura_step = Some(U256::from(u.arbitrary::<u64>()?));
+            header.aura_seal = Some(u.arbitrary()?);
+            // AuRa sealed blocks up to the merge, which came after London and before Shanghai
+            1
+        };
+
+        let forks = u.int_in_range(0..=max_forks)?;
+        header.base_fee_per_gas = (forks > 0).then(|| u.arbitrary()).transpose()?;
+        header.withdrawals_root = (forks > 1).then(|| u.arbitrary()).transpose()?;
+        header.blob_gas_used = (forks > 2).then(|| u.arbitrary()).transpose()?;
+        header.excess_blob_gas = (forks > 3).then(|| u.arbitrary()).transpose()?;
+        header.parent_beacon_block_root = (forks > 4).then(|| u.arbitrary()).transpose()?;
+        header.requests_hash = (forks > 5).then(|| u.arbitrary()).transpose()?;
+        header.block_access_list_hash = (forks > 6).then(|| u.arbitrary()).transpose()?;
+        Ok(header)
+    }
+}
+
+/// Proptest strategy built on the [`arbitrary::Arbitrary`] implementation, so that both emit the
+/// same shapes.
+#[cfg(any(test, feature = "arbitrary"))]
+impl proptest::arbitrary::Arbitrary for GnosisHeader {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        use proptest::prelude::*;
+
+        proptest::collection::vec(any::<u8>(), 1024)
+            .prop_filter_map("not enough entropy for a header", |bytes| {
+                <Self as arbitrary::Arbitrary>::arbitrary(&mut arbitrary::Unstructured::new(&bytes))
+                    .ok()
+            })
+            .boxed()
+    }
+}
+
 // tests
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::blob_schedule::GNOSIS_BLOB_PARAMS;
     use alloy_primitives::{B256, b256};
 
     fn get_sample_pre_merge_header() -> GnosisHeader {
@@ -1076,6 +1649,8 @@ mod tests {
             excess_blob_gas: None,
             parent_beacon_block_root: None,
             requests_hash: None,
+            block_access_list_hash: None,
+            trailing_fields: Vec::new(),
         }
     }
 
@@ -1106,6 +1681,8 @@ mod tests {
             excess_blob_gas: None,
             parent_beacon_block_root: None,
             requests_hash: None,
+            block_access_list_hash: None,
+            trailing_fields: Vec::new(),
         }
     }
 
@@ -1267,6 +1844,24 @@ mod tests {
         assert_ne!(hash1, B256::ZERO, "Hash should not be zero");
     }
 
+    #[test]
+    fn test_aura_seal_hash() {
+        let header = get_sample_pre_merge_header();
+        let seal_hash = header.aura_seal_hash();
+        assert_ne!(seal_hash, header.hash_slow());
+
+        // The seal fields are not part of the signed hash
+        let mut resealed = header.clone();
+        resealed.aura_step = Some(U256::from(1));
+        resealed.aura_seal = Some(FixedBytes::repeat_byte(0xff));
+        assert_eq!(resealed.aura_seal_hash(), seal_hash);
+        assert_ne!(resealed.hash_slow(), header.hash_slow());
+
+        // Every other field is
+        resealed.gas_used += 1;
+        assert_ne!(resealed.aura_seal_hash(), seal_hash);
+    }
+
     #[test]
     fn test_ommers_hash_is_empty() {
         let mut header = get_sample_post_merge_header();
@@ -1351,6 +1946,242 @@ mod tests {
         assert!(header.prague_active());
     }
 
+    #[test]
+    fn test_amsterdam_active() {
+        let mut header = get_sample_post_merge_header();
+        header.block_access_list_hash = None;
+        assert!(!header.amsterdam_active());
+
+        header.block_access_list_hash = Some(B256::ZERO);
+        assert!(header.amsterdam_active());
+    }
+
+    #[test]
+    fn test_amsterdam_header_roundtrips() {
+        let header = get_sample_amsterdam_header();
+        assert!(header.prague_active());
+        assert!(header.amsterdam_active());
+
+        let mut buf = Vec::new();
+        header.encode(&mut buf);
+        assert_eq!(buf.len(), header.length());
+        let decoded = GnosisHeader::decode(&mut &buf[..]).unwrap();
+        assert_eq!(decoded, header);
+
+        let compressed = header.clone().compress();
+        assert_eq!(GnosisHeader::decompress(&compressed).unwrap(), header);
+
+        let json = serde_json::to_string(&header).unwrap();
+        assert!(json.contains("blockAccessListHash"));
+        let from_json: GnosisHeader = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json, header);
+    }
+
+    #[test]
+    fn test_block_access_list_hash_changes_hash() {
+        let header = get_sample_amsterdam_header();
+        let mut prague = header.clone();
+        prague.block_access_list_hash = None;
+        assert_ne!(header.hash_slow(), prague.hash_slow());
+    }
+
+    #[test]
+    fn test_alloy_header_drops_block_access_list_hash() {
+        let header = get_sample_amsterdam_header();
+        let alloy_header = header.to_alloy_header();
+        assert_eq!(alloy_header.requests_hash, header.requests_hash);
+
+        let back: GnosisHeader = alloy_header.into();
+        assert!(back.block_access_list_hash.is_none());
+        assert!(!back.amsterdam_active());
+    }
+
+    /// The compact layout headers were stored with before the extension was introduced.
+    #[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize, Compact)]
+    struct BaselineCompactHeader {
+        parent_hash: B256,
+        ommers_hash: B256,
+        beneficiary: Address,
+        state_root: B256,
+        transactions_root: B256,
+        receipts_root: B256,
+        withdrawals_root: Option<B256>,
+        logs_bloom: Bloom,
+        difficulty: U256,
+        number: BlockNumber,
+        gas_limit: u64,
+        gas_used: u64,
+        timestamp: u64,
+        mix_hash: Option<B256>,
+        nonce: Option<u64>,
+        aura_step: Option<U256>,
+        aura_seal: Option<FixedBytes<65>>,
+        base_fee_per_gas: Option<u64>,
+        blob_gas_used: Option<u64>,
+        excess_blob_gas: Option<u64>,
+        parent_beacon_block_root: Option<B256>,
+        requests_hash: Option<B256>,
+        extra_data: Bytes,
+    }
+
+    /// The bincode layout headers were serialized with before the extension was introduced.
+    #[derive(Debug, Serialize, Deserialize)]
+    struct BaselineBincodeHeader {
+        parent_hash: B256,
+        ommers_hash: B256,
+        beneficiary: Address,
+        state_root: B256,
+        transactions_root: B256,
+        receipts_root: B256,
+        withdrawals_root: Option<B256>,
+        logs_bloom: Bloom,
+        difficulty: U256,
+        number: BlockNumber,
+        gas_limit: u64,
+        gas_used: u64,
+        timestamp: u64,
+        mix_hash: Option<B256>,
+        nonce: Option<B64>,
+        aura_step: Option<U256>,
+        aura_seal: Option<FixedBytes<65>>,
+        base_fee_per_gas: Option<u64>,
+        blob_gas_used: Option<u64>,
+        excess_blob_gas: Option<u64>,
+        parent_beacon_block_root: Option<B256>,
+        requests_hash: Option<B256>,
+        extra_data: Bytes,
+    }
+
+    fn get_sample_prague_header() -> GnosisHeader {
+        let mut header = get_sample_amsterdam_header();
+        header.block_access_list_hash = None;
+        header
+    }
+
+    #[test]
+    fn test_decompress_baseline_layout() {
+        for header in [
+            get_sample_pre_merge_header(),
+            get_sample_post_merge_header(),
+            get_sample_prague_header(),
+        ] {
+            let baseline = BaselineCompactHeader {
+                parent_hash: header.parent_hash,
+                ommers_hash: header.ommers_hash,
+                beneficiary: header.beneficiary,
+                state_root: header.state_root,
+                transactions_root: header.transactions_root,
+                receipts_root: header.receipts_root,
+                withdrawals_root: header.withdrawals_root,
+                logs_bloom: header.logs_bloom,
+                difficulty: header.difficulty,
+                number: header.number,
+                gas_limit: header.gas_limit,
+                gas_used: header.gas_used,
+                timestamp: header.timestamp,
+                mix_hash: header.mix_hash,
+                nonce: header.nonce.map(Into::into),
+                aura_step: header.aura_step,
+                aura_seal: header.aura_seal,
+                base_fee_per_gas: header.base_fee_per_gas,
+                blob_gas_used: header.blob_gas_used,
+                excess_blob_gas: header.excess_blob_gas,
+                parent_beacon_block_root: header.parent_beacon_block_root,
+                requests_hash: header.requests_hash,
+                extra_data: header.extra_data.clone(),
+            };
+            let mut stored = Vec::new();
+            baseline.to_compact(&mut stored);
+
+            assert_eq!(GnosisHeader::decompress(&stored).unwrap(), header);
+            assert_eq!(header.clone().compress(), stored);
+        }
+    }
+
+    #[test]
+    fn test_try_from_compact_rejects_corrupted_framing() {
+        let header = get_sample_amsterdam_header();
+        let mut stored = Vec::new();
+        let len = header.to_compact(&mut stored);
+        stored.extend_from_slice(b"next");
+        assert_eq!(
+            GnosisHeader::try_from_compact(&stored, len),
+            Some((header, &b"next"[..]))
+        );
+
+        // Lengths past the end of the buffer
+        assert_eq!(
+            GnosisHeader::try_from_compact(&stored, stored.len() + 1),
+            None
+        );
+        let mut oversized = stored[..len].to_vec();
+        oversized[4..8].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert_eq!(GnosisHeader::try_from_compact(&oversized, len), None);
+        assert_eq!(
+            GnosisHeader::try_from_compact(&COMPACT_HEADER_EXT_MARKER, 4),
+            None
+        );
+
+        // Truncated headers, and a seal-less one
+        assert_eq!(GnosisHeader::try_from_compact(&stored, len / 2), None);
+        let mut unsealed = get_sample_post_merge_header();
+        unsealed.mix_hash = None;
+        let mut buf = Vec::new();
+        let len = unsealed.to_compact(&mut buf);
+        assert_eq!(GnosisHeader::try_from_compact(&buf, len), None);
+        assert_eq!(GnosisHeader::decompress(&buf), Err(DatabaseError::Decode));
+    }
+
+    #[test]
+    fn test_bincode_baseline_layout() {
+        let header = get_sample_prague_header();
+        let baseline = BaselineBincodeHeader {
+            parent_hash: header.parent_hash,
+            ommers_hash: header.ommers_hash,
+            beneficiary: header.beneficiary,
+            state_root: header.state_root,
+            transactions_root: header.transactions_root,
+            receipts_root: header.receipts_root,
+            withdrawals_root: header.withdrawals_root,
+            logs_bloom: header.logs_bloom,
+            difficulty: header.difficulty,
+            number: header.number,
+            gas_limit: header.gas_limit,
+            gas_used: header.gas_used,
+            timestamp: header.timestamp,
+            mix_hash: header.mix_hash,
+            nonce: header.nonce,
+            aura_step: header.aura_step,
+            aura_seal: header.aura_seal,
+            base_fee_per_gas: header.base_fee_per_gas,
+            blob_gas_used: header.blob_gas_used,
+            excess_blob_gas: header.excess_blob_gas,
+            parent_beacon_block_root: header.parent_beacon_block_root,
+            requests_hash: header.requests_hash,
+            extra_data: header.extra_data.clone(),
+        };
+
+        // Every baseline field keeps its position, and an absent extension is a single `None` tag
+        let mut expected = bincode::serialize(&baseline).unwrap();
+        expected.push(0);
+        let data = BincodeCompatHeader { header };
+        assert_eq!(bincode::serialize(&data).unwrap(), expected);
+        let decoded: BincodeCompatHeader = bincode::deserialize(&expected).unwrap();
+        assert_eq!(decoded.header, data.header);
+
+        // The block access list hash is appended after the baseline fields
+        let data = BincodeCompatHeader {
+            header: get_sample_amsterdam_header(),
+        };
+        let encoded = bincode::serialize(&data).unwrap();
+        assert_eq!(
+            &encoded[..expected.len() - 1],
+            &expected[..expected.len() - 1]
+        );
+        let decoded: BincodeCompatHeader = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(decoded.header, data.header);
+    }
+
     #[test]
     fn test_header_equality() {
         let header1 = get_sample_post_merge_header();
@@ -1567,6 +2398,43 @@ mod tests {
         assert!(header.next_block_excess_blob_gas(blob_params).is_none());
     }
 
+    #[test]
+    fn test_scheduled_blob_helpers() {
+        let mut header = get_sample_post_merge_header();
+        header.timestamp = 1_000;
+        header.blob_gas_used = Some(131_072);
+        header.excess_blob_gas = Some(1_000_000);
+
+        let bpo = BlobParams {
+            target_blob_count: 2,
+            max_blob_count: 4,
+            ..GNOSIS_BLOB_PARAMS
+        };
+        let schedule = BlobSchedule::new([(1_000, GNOSIS_BLOB_PARAMS), (1_005, bpo)]);
+
+        assert_eq!(header.blob_params(&schedule), Some(GNOSIS_BLOB_PARAMS));
+        assert_eq!(header.max_blob_count(&schedule), Some(2));
+        assert_eq!(
+            header.scheduled_blob_fee(&schedule),
+            header.blob_fee(GNOSIS_BLOB_PARAMS)
+        );
+
+        // The next block crosses into the blob-parameter-only fork
+        assert_eq!(
+            header.scheduled_next_block_excess_blob_gas(&schedule, 1_005),
+            header.next_block_excess_blob_gas(bpo)
+        );
+        assert_eq!(
+            header.scheduled_next_block_blob_fee(&schedule, 1_005),
+            header.next_block_blob_fee(bpo)
+        );
+
+        // Nothing is scheduled before the first entry
+        header.timestamp = 999;
+        assert!(header.scheduled_blob_fee(&schedule).is_none());
+        assert!(header.max_blob_count(&schedule).is_none());
+    }
+
     #[test]
     fn test_header_with_all_eip_fields() {
         let header = GnosisHeader {
@@ -1593,6 +2461,8 @@ mod tests {
             excess_blob_gas: Some(50000),
             parent_beacon_block_root: Some(B256::from([9u8; 32])),
             requests_hash: Some(B256::from([10u8; 32])),
+            block_access_list_hash: None,
+            trailing_fields: Vec::new(),
         };
 
         assert!(header.shanghai_active());
@@ -1636,6 +2506,8 @@ mod tests {
             excess_blob_gas: None,
             parent_beacon_block_root: None,
             requests_hash: None,
+            block_access_list_hash: None,
+            trailing_fields: Vec::new(),
         };
 
         assert!(header.is_pre_merge());
@@ -2094,10 +2966,269 @@ mod tests {
         );
     }
 
+    fn get_sample_amsterdam_header() -> GnosisHeader {
+        let mut header = get_sample_post_merge_header();
+        header.withdrawals_root = Some(B256::from([8u8; 32]));
+        header.blob_gas_used = Some(0);
+        header.excess_blob_gas = Some(0);
+        header.parent_beacon_block_root = Some(B256::from([9u8; 32]));
+        header.requests_hash = Some(B256::from([10u8; 32]));
+        header.block_access_list_hash = Some(B256::from([12u8; 32]));
+        header
+    }
+
+    /// Appends raw RLP items to an encoded header, as a future fork would.
+    fn append_fields(encoded: &[u8], fields: &[&[u8]]) -> Vec<u8> {
+        let mut payload = encoded;
+        let head = alloy_rlp::Header::decode(&mut payload).unwrap();
+        let mut payload = payload[..head.payload_length].to_vec();
+        for field in fields {
+            payload.extend_from_slice(field);
+        }
+        let mut out = Vec::new();
+        alloy_rlp::Header {
+            list: true,
+            payload_length: payload.len(),
+        }
+        .encode(&mut out);
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    #[test]
+    fn test_strict_decode_rejects_trailing_fields() {
+        let header = get_sample_amsterdam_header();
+        let mut buf = Vec::new();
+        header.encode(&mut buf);
+
+        let extended = append_fields(&buf, &[&[0x2a]]);
+        assert!(matches!(
+            GnosisHeader::decode(&mut &extended[..]),
+            Err(alloy_rlp::Error::ListLengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_forward_compatible_decode_preserves_trailing_fields() {
+        let header = get_sample_amsterdam_header();
+        let mut buf = Vec::new();
+        header.encode(&mut buf);
+
+        let mut unknown_hash = Vec::new();
+        B256::from([11u8; 32]).encode(&mut unknown_hash);
+        let extended = append_fields(&buf, &[&unknown_hash, &[0x2a]]);
+
+        let decoded = GnosisHeader::decode_forward_compatible(&mut &extended[..]).unwrap();
+        assert!(decoded.has_trailing_fields());
+        assert_eq!(
+            decoded.trailing_fields,
+            vec![Bytes::from(unknown_hash), Bytes::from_static(&[0x2a])]
+        );
+        assert_eq!(decoded.requests_hash, header.requests_hash);
+
+        // Re-encoding reproduces the original bytes, and therefore the block hash
+        let mut reencoded = Vec::new();
+        decoded.encode(&mut reencoded);
+        assert_eq!(reencoded, extended);
+        assert_eq!(reencoded.len(), decoded.length());
+        assert_eq!(decoded.hash_slow(), keccak256(&extended));
+    }
+
+    #[test]
+    fn test_forward_compatible_decode_keeps_other_field_after_requests_hash() {
+        let header = get_sample_prague_header();
+        let mut buf = Vec::new();
+        header.encode(&mut buf);
+
+        // A fork appending something else than a block access list hash after the requests hash
+        let mut unknown_list = Vec::new();
+        vec![1u64, 2].encode(&mut unknown_list);
+        for field in [&[0x2a][..], &unknown_list] {
+            let extended = append_fields(&buf, &[field]);
+            assert!(GnosisHeader::decode(&mut &extended[..]).is_err());
+
+            let decoded = GnosisHeader::decode_forward_compatible(&mut &extended[..]).unwrap();
+            assert_eq!(decoded.block_access_list_hash, None);
+            assert_eq!(decoded.trailing_fields, vec![Bytes::copy_from_slice(field)]);
+            assert_eq!(decoded.requests_hash, header.requests_hash);
+            assert_eq!(alloy_rlp::encode(&decoded), extended);
+            assert_eq!(decoded.hash_slow(), keccak256(&extended));
+        }
+    }
+
+    #[test]
+    fn test_forward_compatible_decode_without_trailing_fields() {
+        let header = get_sample_pre_merge_header();
+        let mut buf = Vec::new();
+        header.encode(&mut buf);
+
+        let decoded = GnosisHeader::decode_forward_compatible(&mut &buf[..]).unwrap();
+        assert!(!decoded.has_trailing_fields());
+        assert_eq!(decoded, header);
+    }
+
+    #[test]
+    fn test_forward_compatible_decode_rejects_truncated_trailing_field() {
+        let header = get_sample_amsterdam_header();
+        let mut buf = Vec::new();
+        header.encode(&mut buf);
+
+        // A string header announcing 32 bytes with only one byte of payload
+        let extended = append_fields(&buf, &[&[0xa0, 0x01]]);
+        assert!(GnosisHeader::decode_forward_compatible(&mut &extended[..]).is_err());
+    }
+
+    #[test]
+    fn test_trailing_fields_storage_roundtrip() {
+        let mut header = get_sample_amsterdam_header();
+        header.trailing_fields = vec![Bytes::from_static(&[0x2a]), Bytes::from_static(&[0x80])];
+
+        let compressed = header.clone().compress();
+        assert!(compressed.starts_with(&COMPACT_HEADER_EXT_MARKER));
+        assert_eq!(GnosisHeader::decompress(&compressed).unwrap(), header);
+
+        // Without extension fields the header is stored in the baseline layout
+        let mut plain = header.clone();
+        plain.trailing_fields.clear();
+        plain.block_access_list_hash = None;
+        assert!(
+            !plain
+                .clone()
+                .compress()
+                .starts_with(&COMPACT_HEADER_EXT_MARKER)
+        );
+
+        let data = BincodeCompatHeader { header };
+        let encoded = bincode::serialize(&data).unwrap();
+        let decoded: BincodeCompatHeader = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(decoded.header, data.header);
+        let header = data.header;
+
+        let json = serde_json::to_string(&header).unwrap();
+        assert!(json.contains("trailingFields"));
+        let from_json: GnosisHeader = serde_json::from_str(&json).unwrap();
+        assert_eq!(from_json, header);
+    }
+
     #[test]
     fn test_header_set_number() {
         let mut header = get_sample_post_merge_header();
         header.set_number(42);
         assert_eq!(header.number, 42);
     }
+
+    #[test]
+    fn test_pre_merge_serde_json_roundtrip() {
+        let header = get_sample_pre_merge_header();
+        let json = serde_json::to_string(&header).unwrap();
+        let decoded: GnosisHeader = serde_json::from_str(&json).unwrap();
+        assert!(decoded.is_pre_merge() && !decoded.is_post_merge());
+        assert_eq!(decoded.hash_slow(), header.hash_slow());
+
+        // Without AuRa fields, a missing mix hash and nonce still default to zero
+        let mut value = serde_json::to_value(get_sample_post_merge_header()).unwrap();
+        value.as_object_mut().unwrap().remove("mixHash");
+        value.as_object_mut().unwrap().remove("nonce");
+        let decoded: GnosisHeader = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.mix_hash, Some(B256::ZERO));
+        assert_eq!(decoded.nonce, Some(B64::ZERO));
+    }
+
+    #[test]
+    fn test_pre_merge_serde_json_keeps_mix_hash() {
+        // A mix hash and nonce given next to the AuRa fields are kept, as they always were
+        let mut value = serde_json::to_value(get_sample_pre_merge_header()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.insert(
+            "mixHash".into(),
+            serde_json::to_value(B256::with_last_byte(1)).unwrap(),
+        );
+        object.insert(
+            "nonce".into(),
+            serde_json::to_value(B64::with_last_byte(2)).unwrap(),
+        );
+        let decoded: GnosisHeader = serde_json::from_value(value).unwrap();
+        assert_eq!(decoded.mix_hash, Some(B256::with_last_byte(1)));
+        assert_eq!(decoded.nonce, Some(B64::with_last_byte(2)));
+        assert!(decoded.is_pre_merge());
+    }
+
+    #[test]
+    fn test_rejects_headers_without_seal() {
+        // An AuRa step without a seal gets no default mix hash and nonce
+        let mut value = serde_json::to_value(get_sample_pre_merge_header()).unwrap();
+        value.as_object_mut().unwrap().remove("auraSeal");
+        assert!(serde_json::from_value::<GnosisHeader>(value).is_err());
+
+        let mut header = get_sample_pre_merge_header();
+        header.aura_seal = None;
+        assert!(!header.has_complete_seal());
+        assert_eq!(
+            GnosisHeader::decompress(&header.clone().compress()),
+            Err(DatabaseError::Decode)
+        );
+        let encoded = bincode::serialize(&BincodeCompatHeader { header }).unwrap();
+        assert!(bincode::deserialize::<BincodeCompatHeader>(&encoded).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "neither a mix hash and nonce nor an AuRa step and seal")]
+    fn test_encode_requires_seal() {
+        let mut header = get_sample_post_merge_header();
+        header.nonce = None;
+        let _ = header.hash_slow();
+    }
+
+    #[serde_with::serde_as]
+    #[derive(Debug, Serialize, Deserialize)]
+    struct BincodeCompatHeader {
+        #[serde_as(as = "serde_bincode_compat::GnosisHeader")]
+        header: GnosisHeader,
+    }
+
+    proptest::proptest! {
+        #[test]
+        fn proptest_header_shape(header: GnosisHeader) {
+            proptest::prop_assert!(header.is_pre_merge() != header.is_post_merge());
+            proptest::prop_assert!(!header.has_trailing_fields());
+            if header.is_pre_merge() {
+                proptest::prop_assert!(header.withdrawals_root.is_none());
+            }
+            if header.requests_hash.is_some() {
+                proptest::prop_assert!(header.prague_active());
+            }
+        }
+
+        #[test]
+        fn proptest_rlp_roundtrip(header: GnosisHeader) {
+            let mut buf = Vec::new();
+            header.encode(&mut buf);
+            proptest::prop_assert_eq!(buf.len(), header.length());
+            proptest::prop_assert_eq!(GnosisHeader::decode(&mut buf.as_slice()).unwrap(), header);
+        }
+
+        #[test]
+        fn proptest_compact_roundtrip(header: GnosisHeader) {
+            let mut buf = Vec::new();
+            let len = header.to_compact(&mut buf);
+            proptest::prop_assert_eq!(GnosisHeader::from_compact(&buf, len).0, header.clone());
+
+            let compressed = header.clone().compress();
+            proptest::prop_assert_eq!(GnosisHeader::decompress(&compressed).unwrap(), header);
+        }
+
+        #[test]
+        fn proptest_serde_json_roundtrip(header: GnosisHeader) {
+            let json = serde_json::to_string(&header).unwrap();
+            proptest::prop_assert_eq!(serde_json::from_str::<GnosisHeader>(&json).unwrap(), header);
+        }
+
+        #[test]
+        fn proptest_bincode_compat_roundtrip(header: GnosisHeader) {
+            let data = BincodeCompatHeader { header };
+            let encoded = bincode::serialize(&data).unwrap();
+            let decoded: BincodeCompatHeader = bincode::deserialize(&encoded).unwrap();
+            proptest::prop_assert_eq!(decoded.header, data.header);
+        }
+    }
 }
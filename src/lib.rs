@@ -1 +1,24 @@
+pub mod aura;
+pub mod beacon_proof;
+pub mod blob_schedule;
+pub mod block_rewards;
+pub mod chain_file;
+pub mod chainspec;
+pub mod devnet;
+pub mod equivocation;
+pub mod era1;
+pub mod eth_wire;
+pub mod fee_collector;
+pub mod finality;
+pub mod fork_id;
+#[cfg(test)]
+mod fuzz;
 pub mod header;
+pub mod mmr;
+pub mod requests;
+pub mod slot_clock;
+pub mod step_schedule;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+pub mod validator_set;
+pub mod withdrawals;
@@ -0,0 +1,376 @@
+use alloy_primitives::{B256, BlockNumber, keccak256};
+
+use crate::header::GnosisHeader;
+
+/// Errors returned by the [`HeaderMmr`] and [`verify_inclusion`].
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum MmrError {
+    /// Headers were not appended in order.
+    #[display("expected block {expected}, got {got}")]
+    NotConsecutive {
+        /// The next block number the accumulator expects.
+        expected: BlockNumber,
+        /// The block number that was appended.
+        got: BlockNumber,
+    },
+    /// The header does not build on the last appended header.
+    #[display("block {_0} does not build on the previous header")]
+    ParentHashMismatch(#[error(not(source))] BlockNumber),
+    /// The block is not in the accumulator.
+    #[display("block {_0} is not in the accumulator")]
+    UnknownBlock(#[error(not(source))] BlockNumber),
+    /// The proof does not have the shape its leaf index and leaf count require.
+    #[display("malformed proof")]
+    MalformedProof,
+    /// The proof does not lead to the expected root.
+    #[display("proof leads to {got}, expected {expected}")]
+    RootMismatch {
+        /// The trusted root.
+        expected: B256,
+        /// The root the proof leads to.
+        got: B256,
+    },
+}
+
+/// Proof that a header hash is a leaf of a [`HeaderMmr`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MmrProof {
+    /// Position of the header among the leaves.
+    pub leaf_index: u64,
+    /// Number of leaves of the accumulator the proof was made against.
+    pub leaf_count: u64,
+    /// Siblings on the path from the leaf to the peak of its mountain, lowest first.
+    pub siblings: Vec<B256>,
+    /// Peaks of every mountain, highest mountain first. The peak of the leaf's own mountain is
+    /// left out, the verifier recomputes it.
+    pub peaks: Vec<B256>,
+}
+
+/// Append-only Merkle Mountain Range over header hashes.
+///
+/// Every appended header becomes a leaf. Leaves are merged into perfect binary trees, the
+/// mountains, with `keccak256(left || right)`, and the root commits to the peaks of all mountains
+/// and the number of leaves. Proofs are logarithmic in the number of leaves and remain valid for
+/// the root they were made against, however many headers are appended afterward.
+///
+/// All nodes are kept in memory, so that a proof can be made for any appended header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderMmr {
+    /// Number of the first appended header.
+    first_block: BlockNumber,
+    /// Nodes per height, the leaves first.
+    levels: Vec<Vec<B256>>,
+}
+
+impl HeaderMmr {
+    /// Creates an empty accumulator whose first header will be `first_block`.
+    pub fn new(first_block: BlockNumber) -> Self {
+        Self {
+            first_block,
+            levels: vec![Vec::new()],
+        }
+    }
+
+    /// Returns the number of appended headers.
+    pub fn leaf_count(&self) -> u64 {
+        self.levels[0].len() as u64
+    }
+
+    /// Returns the hash of the last appended header, if any.
+    pub fn last_hash(&self) -> Option<B256> {
+        self.levels[0].last().copied()
+    }
+
+    /// Appends the next header.
+    ///
+    /// The header must follow the last appended one, both by number and by parent hash.
+    pub fn push(&mut self, header: &GnosisHeader) -> Result<(), MmrError> {
+        let expected = self.first_block + self.leaf_count();
+        if header.number != expected {
+            return Err(MmrError::NotConsecutive {
+                expected,
+                got: header.number,
+            });
+        }
+        if self
+            .last_hash()
+            .is_some_and(|parent| parent != header.parent_hash)
+        {
+            return Err(MmrError::ParentHashMismatch(header.number));
+        }
+
+        let mut node = header.hash_slow();
+        let mut height = 0;
+        loop {
+            self.levels[height].push(node);
+            let len = self.levels[height].len();
+            if len % 2 == 1 {
+                break;
+            }
+            node = hash_pair(self.levels[height][len - 2], node);
+            height += 1;
+            if self.levels.len() == height {
+                self.levels.push(Vec::new());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the peaks of all mountains, highest mountain first.
+    pub fn peaks(&self) -> Vec<B256> {
+        let mut peaks = Vec::new();
+        let mut offset = 0;
+        for height in mountain_heights(self.leaf_count()) {
+            peaks.push(self.levels[height][(offset >> height) as usize]);
+            offset += 1 << height;
+        }
+        peaks
+    }
+
+    /// Returns the root of the accumulator.
+    pub fn root(&self) -> B256 {
+        bag_peaks(self.leaf_count(), &self.peaks())
+    }
+
+    /// Returns the inclusion proof of the given block against the current root.
+    pub fn prove(&self, block: BlockNumber) -> Result<MmrProof, MmrError> {
+        let leaf_count = self.leaf_count();
+        let leaf_index = block
+            .checked_sub(self.first_block)
+            .filter(|index| *index < leaf_count)
+            .ok_or(MmrError::UnknownBlock(block))?;
+
+        let (mountain, height, offset) = locate(leaf_index, leaf_count);
+        let position = leaf_index - offset;
+        let siblings = (0..height)
+            .map(|level| {
+                let index = (offset >> level) + ((position >> level) ^ 1);
+                self.levels[level][index as usize]
+            })
+            .collect();
+        let mut peaks = self.peaks();
+        peaks.remove(mountain);
+        Ok(MmrProof {
+            leaf_index,
+            leaf_count,
+            siblings,
+            peaks,
+        })
+    }
+}
+
+/// Checks that `header` is included in the accumulator with the given root.
+pub fn verify_inclusion(
+    header: &GnosisHeader,
+    proof: &MmrProof,
+    root: B256,
+) -> Result<(), MmrError> {
+    if proof.leaf_index >= proof.leaf_count {
+        return Err(MmrError::MalformedProof);
+    }
+    let (mountain, height, offset) = locate(proof.leaf_index, proof.leaf_count);
+    if proof.siblings.len() != height
+        || proof.peaks.len() + 1 != mountain_heights(proof.leaf_count).count()
+    {
+        return Err(MmrError::MalformedProof);
+    }
+
+    let position = proof.leaf_index - offset;
+    let mut node = header.hash_slow();
+    for (level, sibling) in proof.siblings.iter().enumerate() {
+        node = if (position >> level) & 1 == 0 {
+            hash_pair(node, *sibling)
+        } else {
+            hash_pair(*sibling, node)
+        };
+    }
+    let mut peaks = proof.peaks.clone();
+    peaks.insert(mountain, node);
+
+    let got = bag_peaks(proof.leaf_count, &peaks);
+    if got != root {
+        return Err(MmrError::RootMismatch {
+            expected: root,
+            got,
+        });
+    }
+    Ok(())
+}
+
+fn hash_pair(left: B256, right: B256) -> B256 {
+    let mut data = [0; 64];
+    data[..32].copy_from_slice(left.as_slice());
+    data[32..].copy_from_slice(right.as_slice());
+    keccak256(data)
+}
+
+/// Returns the heights of the mountains of an accumulator with `leaf_count` leaves, highest
+/// first.
+fn mountain_heights(leaf_count: u64) -> impl Iterator<Item = usize> {
+    (0..u64::BITS as usize)
+        .rev()
+        .filter(move |height| leaf_count & (1 << height) != 0)
+}
+
+/// Returns the index, height and first leaf of the mountain holding the given leaf.
+fn locate(leaf_index: u64, leaf_count: u64) -> (usize, usize, u64) {
+    let mut offset = 0;
+    for (mountain, height) in mountain_heights(leaf_count).enumerate() {
+        if leaf_index < offset + (1 << height) {
+            return (mountain, height, offset);
+        }
+        offset += 1 << height;
+    }
+    unreachable!("leaf index is below the leaf count")
+}
+
+/// Folds the peaks from the lowest mountain up, then commits to the number of leaves.
+///
+/// The root of an empty accumulator is zero.
+fn bag_peaks(leaf_count: u64, peaks: &[B256]) -> B256 {
+    let Some(bagged) = peaks
+        .iter()
+        .rev()
+        .copied()
+        .reduce(|bagged, peak| hash_pair(peak, bagged))
+    else {
+        return B256::ZERO;
+    };
+    hash_pair(B256::left_padding_from(&leaf_count.to_be_bytes()), bagged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B64;
+
+    fn chain(count: u64) -> Vec<GnosisHeader> {
+        let mut headers: Vec<GnosisHeader> = Vec::new();
+        for number in 0..count {
+            headers.push(GnosisHeader {
+                number,
+                parent_hash: headers
+                    .last()
+                    .map(GnosisHeader::hash_slow)
+                    .unwrap_or_default(),
+                mix_hash: Some(B256::ZERO),
+                nonce: Some(B64::ZERO),
+                ..Default::default()
+            });
+        }
+        headers
+    }
+
+    #[test]
+    fn test_prove_every_header() {
+        let headers = chain(23);
+        let mut mmr = HeaderMmr::new(0);
+        assert_eq!(mmr.root(), B256::ZERO);
+
+        for (count, header) in headers.iter().enumerate() {
+            mmr.push(header).unwrap();
+            let root = mmr.root();
+            assert_eq!(mmr.peaks().len(), (count as u64 + 1).count_ones() as usize);
+
+            for proven in &headers[..=count] {
+                let proof = mmr.prove(proven.number).unwrap();
+                assert_eq!(verify_inclusion(proven, &proof, root), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_single_and_two_leaves() {
+        let headers = chain(2);
+        let mut mmr = HeaderMmr::new(0);
+        mmr.push(&headers[0]).unwrap();
+        assert_eq!(
+            mmr.root(),
+            hash_pair(B256::with_last_byte(1), headers[0].hash_slow())
+        );
+
+        mmr.push(&headers[1]).unwrap();
+        let peak = hash_pair(headers[0].hash_slow(), headers[1].hash_slow());
+        assert_eq!(mmr.peaks(), vec![peak]);
+        assert_eq!(mmr.root(), hash_pair(B256::with_last_byte(2), peak));
+    }
+
+    #[test]
+    fn test_old_proofs_stay_valid() {
+        let headers = chain(40);
+        let mut mmr = HeaderMmr::new(0);
+        for header in &headers[..10] {
+            mmr.push(header).unwrap();
+        }
+        let (root, proof) = (mmr.root(), mmr.prove(3).unwrap());
+        for header in &headers[10..] {
+            mmr.push(header).unwrap();
+        }
+        assert_eq!(verify_inclusion(&headers[3], &proof, root), Ok(()));
+        assert!(matches!(
+            verify_inclusion(&headers[3], &proof, mmr.root()),
+            Err(MmrError::RootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_invalid_proofs() {
+        let headers = chain(11);
+        let mut mmr = HeaderMmr::new(0);
+        for header in &headers {
+            mmr.push(header).unwrap();
+        }
+        let root = mmr.root();
+        let proof = mmr.prove(5).unwrap();
+
+        assert!(matches!(
+            verify_inclusion(&headers[6], &proof, root),
+            Err(MmrError::RootMismatch { .. })
+        ));
+
+        let mut tampered = proof.clone();
+        tampered.siblings[0] = B256::ZERO;
+        assert!(verify_inclusion(&headers[5], &tampered, root).is_err());
+
+        let mut tampered = proof.clone();
+        tampered.siblings.pop();
+        assert_eq!(
+            verify_inclusion(&headers[5], &tampered, root),
+            Err(MmrError::MalformedProof)
+        );
+
+        let tampered = MmrProof {
+            leaf_index: 11,
+            ..proof
+        };
+        assert_eq!(
+            verify_inclusion(&headers[5], &tampered, root),
+            Err(MmrError::MalformedProof)
+        );
+    }
+
+    #[test]
+    fn test_push_errors() {
+        let headers = chain(3);
+        let mut mmr = HeaderMmr::new(1);
+        assert_eq!(
+            mmr.push(&headers[0]),
+            Err(MmrError::NotConsecutive {
+                expected: 1,
+                got: 0
+            })
+        );
+        mmr.push(&headers[1]).unwrap();
+
+        let mut orphan = headers[2].clone();
+        orphan.parent_hash = B256::ZERO;
+        assert_eq!(mmr.push(&orphan), Err(MmrError::ParentHashMismatch(2)));
+        mmr.push(&headers[2]).unwrap();
+
+        assert_eq!(mmr.prove(0), Err(MmrError::UnknownBlock(0)));
+        assert_eq!(mmr.prove(3), Err(MmrError::UnknownBlock(3)));
+        let proof = mmr.prove(2).unwrap();
+        assert_eq!(proof.leaf_index, 1);
+        assert_eq!(verify_inclusion(&headers[2], &proof, mmr.root()), Ok(()));
+    }
+}
@@ -0,0 +1,444 @@
+use alloy_consensus::TxReceipt;
+use alloy_eips::eip7685::Requests;
+pub use alloy_eips::{
+    eip6110::{DEPOSIT_REQUEST_TYPE, DepositRequest},
+    eip7002::{WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_TYPE, WithdrawalRequest},
+    eip7251::{
+        CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS, CONSOLIDATION_REQUEST_TYPE, ConsolidationRequest,
+    },
+};
+use alloy_primitives::{Address, B256, Bytes, FixedBytes, Log};
+use alloy_sol_types::{SolEvent, sol};
+use sha2::{Digest, Sha256};
+
+use crate::header::GnosisHeader;
+
+sol! {
+    /// Emitted by the deposit contract for every deposit, with the same layout as on Ethereum.
+    ///
+    /// `amount` and `index` are little-endian `uint64`s, the amount in gwei of mGNO.
+    event DepositEvent(
+        bytes pubkey,
+        bytes withdrawal_credentials,
+        bytes amount,
+        bytes signature,
+        bytes index
+    );
+}
+
+/// Size of an encoded [`DepositRequest`].
+pub const DEPOSIT_REQUEST_SIZE: usize = 48 + 32 + 8 + 96 + 8;
+
+/// Size of an encoded [`WithdrawalRequest`], as returned by the EIP-7002 contract.
+pub const WITHDRAWAL_REQUEST_SIZE: usize = 20 + 48 + 8;
+
+/// Size of an encoded [`ConsolidationRequest`], as returned by the EIP-7251 contract.
+pub const CONSOLIDATION_REQUEST_SIZE: usize = 20 + 48 + 48;
+
+/// Errors returned when collecting execution requests or checking them against a header.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum RequestsError {
+    /// A deposit log does not have the layout of a `DepositEvent`.
+    #[display("invalid deposit log: {_0}")]
+    InvalidDepositLog(#[error(not(source))] String),
+    /// A system contract returned data that is not a whole number of requests.
+    #[display("{len} bytes of system call output are not a multiple of the {size} bytes request")]
+    InvalidOutputLength {
+        /// Length of the output.
+        len: usize,
+        /// Size of a single request.
+        size: usize,
+    },
+    /// The header predates Prague and has no requests hash.
+    #[display("header has no requests hash")]
+    MissingRequestsHash,
+    /// The requests hash in the header does not match the requests.
+    #[display("requests hash mismatch: header {header}, computed {computed}")]
+    HashMismatch {
+        /// Hash stored in the header.
+        header: B256,
+        /// Hash computed from the requests.
+        computed: B256,
+    },
+}
+
+/// The execution requests of a Prague block, by type.
+///
+/// On Gnosis, deposits are taken from the `DepositEvent` logs of the deposit contract, as in
+/// EIP-6110, while withdrawal and consolidation requests are dequeued from the same EIP-7002 and
+/// EIP-7251 predeploys as on Ethereum.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GnosisRequests {
+    /// Deposits, in log order.
+    pub deposits: Vec<DepositRequest>,
+    /// Withdrawal requests, in the order returned by the EIP-7002 contract.
+    pub withdrawals: Vec<WithdrawalRequest>,
+    /// Consolidation requests, in the order returned by the EIP-7251 contract.
+    pub consolidations: Vec<ConsolidationRequest>,
+}
+
+impl GnosisRequests {
+    /// Collects the requests of a block from its receipts and the outputs of the withdrawal and
+    /// consolidation request system calls.
+    pub fn collect<R>(
+        deposit_contract: Address,
+        receipts: &[R],
+        withdrawal_output: &[u8],
+        consolidation_output: &[u8],
+    ) -> Result<Self, RequestsError>
+    where
+        R: TxReceipt<Log = Log>,
+    {
+        Ok(Self {
+            deposits: deposit_requests(deposit_contract, receipts)?,
+            withdrawals: decode_withdrawal_requests(withdrawal_output)?,
+            consolidations: decode_consolidation_requests(consolidation_output)?,
+        })
+    }
+
+    /// Returns the requests in their EIP-7685 encoding, each prefixed with its type.
+    ///
+    /// Types without requests are left out.
+    pub fn encode(&self) -> Requests {
+        let mut requests = Requests::default();
+        let mut push = |request_type, data: Vec<u8>| {
+            if !data.is_empty() {
+                requests.push_request_with_type(request_type, data);
+            }
+        };
+        push(
+            DEPOSIT_REQUEST_TYPE,
+            self.deposits.iter().flat_map(encode_deposit).collect(),
+        );
+        push(
+            WITHDRAWAL_REQUEST_TYPE,
+            self.withdrawals
+                .iter()
+                .flat_map(encode_withdrawal)
+                .collect(),
+        );
+        push(
+            CONSOLIDATION_REQUEST_TYPE,
+            self.consolidations
+                .iter()
+                .flat_map(encode_consolidation)
+                .collect(),
+        );
+        requests
+    }
+
+    /// Computes the EIP-7685 requests hash committed to by the header.
+    pub fn requests_hash(&self) -> B256 {
+        requests_hash(&self.encode())
+    }
+}
+
+/// Computes the EIP-7685 requests hash, `sha256(sha256(requests_0) ++ sha256(requests_1) ++ ...)`.
+///
+/// Every entry must start with its request type, and entries must be ordered by type. Entries
+/// holding nothing but the type are skipped. Without any request, the hash is
+/// [`EMPTY_REQUESTS_HASH`](alloy_eips::eip7685::EMPTY_REQUESTS_HASH).
+pub fn requests_hash(requests: &Requests) -> B256 {
+    let mut hasher = Sha256::new();
+    for request in requests.iter().filter(|request| request.len() > 1) {
+        hasher.update(Sha256::digest(request));
+    }
+    B256::new(hasher.finalize().into())
+}
+
+/// Checks that the header commits to the given requests.
+pub fn verify_requests_hash(
+    header: &GnosisHeader,
+    requests: &GnosisRequests,
+) -> Result<(), RequestsError> {
+    let header_hash = header
+        .requests_hash
+        .ok_or(RequestsError::MissingRequestsHash)?;
+    let computed = requests.requests_hash();
+    if header_hash != computed {
+        return Err(RequestsError::HashMismatch {
+            header: header_hash,
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// Parses a `DepositEvent` log into a deposit request.
+///
+/// Returns `None` for logs that are not a `DepositEvent` of `deposit_contract`, and an error for
+/// deposit events whose fields have the wrong size.
+pub fn parse_deposit_log(
+    deposit_contract: Address,
+    log: &Log,
+) -> Result<Option<DepositRequest>, RequestsError> {
+    if log.address != deposit_contract
+        || log.topics().first() != Some(&DepositEvent::SIGNATURE_HASH)
+    {
+        return Ok(None);
+    }
+    let event = DepositEvent::decode_log(log)
+        .map_err(|err| RequestsError::InvalidDepositLog(err.to_string()))?
+        .data;
+
+    let invalid = |field: &str, len: usize| {
+        RequestsError::InvalidDepositLog(format!("{field} is {len} bytes"))
+    };
+    let le_u64 = |field: &str, bytes: &Bytes| {
+        <[u8; 8]>::try_from(bytes.as_ref())
+            .map(u64::from_le_bytes)
+            .map_err(|_| invalid(field, bytes.len()))
+    };
+    Ok(Some(DepositRequest {
+        pubkey: FixedBytes::try_from(event.pubkey.as_ref())
+            .map_err(|_| invalid("pubkey", event.pubkey.len()))?,
+        withdrawal_credentials: B256::try_from(event.withdrawal_credentials.as_ref())
+            .map_err(|_| invalid("withdrawal_credentials", event.withdrawal_credentials.len()))?,
+        amount: le_u64("amount", &event.amount)?,
+        signature: FixedBytes::try_from(event.signature.as_ref())
+            .map_err(|_| invalid("signature", event.signature.len()))?,
+        index: le_u64("index", &event.index)?,
+    }))
+}
+
+/// Extracts the deposit requests of a block from its receipts, in log order.
+pub fn deposit_requests<R>(
+    deposit_contract: Address,
+    receipts: &[R],
+) -> Result<Vec<DepositRequest>, RequestsError>
+where
+    R: TxReceipt<Log = Log>,
+{
+    receipts
+        .iter()
+        .flat_map(|receipt| receipt.logs())
+        .filter_map(|log| parse_deposit_log(deposit_contract, log).transpose())
+        .collect()
+}
+
+/// Decodes the output of the EIP-7002 withdrawal request system call.
+pub fn decode_withdrawal_requests(output: &[u8]) -> Result<Vec<WithdrawalRequest>, RequestsError> {
+    Ok(chunks(output, WITHDRAWAL_REQUEST_SIZE)?
+        .map(|chunk| WithdrawalRequest {
+            source_address: Address::from_slice(&chunk[..20]),
+            validator_pubkey: FixedBytes::from_slice(&chunk[20..68]),
+            amount: u64::from_be_bytes(chunk[68..].try_into().unwrap()),
+        })
+        .collect())
+}
+
+/// Decodes the output of the EIP-7251 consolidation request system call.
+pub fn decode_consolidation_requests(
+    output: &[u8],
+) -> Result<Vec<ConsolidationRequest>, RequestsError> {
+    Ok(chunks(output, CONSOLIDATION_REQUEST_SIZE)?
+        .map(|chunk| ConsolidationRequest {
+            source_address: Address::from_slice(&chunk[..20]),
+            source_pubkey: FixedBytes::from_slice(&chunk[20..68]),
+            target_pubkey: FixedBytes::from_slice(&chunk[68..]),
+        })
+        .collect())
+}
+
+fn chunks(output: &[u8], size: usize) -> Result<std::slice::ChunksExact<'_, u8>, RequestsError> {
+    if !output.len().is_multiple_of(size) {
+        return Err(RequestsError::InvalidOutputLength {
+            len: output.len(),
+            size,
+        });
+    }
+    Ok(output.chunks_exact(size))
+}
+
+/// Encodes a deposit request, with its amount and index little-endian as in the log.
+fn encode_deposit(deposit: &DepositRequest) -> Vec<u8> {
+    let mut data = Vec::with_capacity(DEPOSIT_REQUEST_SIZE);
+    data.extend_from_slice(deposit.pubkey.as_slice());
+    data.extend_from_slice(deposit.withdrawal_credentials.as_slice());
+    data.extend_from_slice(&deposit.amount.to_le_bytes());
+    data.extend_from_slice(deposit.signature.as_slice());
+    data.extend_from_slice(&deposit.index.to_le_bytes());
+    data
+}
+
+/// Encodes a withdrawal request as returned by the contract, with a big-endian amount.
+fn encode_withdrawal(withdrawal: &WithdrawalRequest) -> Vec<u8> {
+    let mut data = Vec::with_capacity(WITHDRAWAL_REQUEST_SIZE);
+    data.extend_from_slice(withdrawal.source_address.as_slice());
+    data.extend_from_slice(withdrawal.validator_pubkey.as_slice());
+    data.extend_from_slice(&withdrawal.amount.to_be_bytes());
+    data
+}
+
+fn encode_consolidation(consolidation: &ConsolidationRequest) -> Vec<u8> {
+    let mut data = Vec::with_capacity(CONSOLIDATION_REQUEST_SIZE);
+    data.extend_from_slice(consolidation.source_address.as_slice());
+    data.extend_from_slice(consolidation.source_pubkey.as_slice());
+    data.extend_from_slice(consolidation.target_pubkey.as_slice());
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::withdrawals::GNOSIS_DEPOSIT_CONTRACT_ADDRESS;
+    use alloy_consensus::{Receipt, ReceiptWithBloom};
+    use alloy_eips::eip7685::EMPTY_REQUESTS_HASH;
+    use alloy_primitives::LogData;
+
+    fn deposit(index: u64) -> DepositRequest {
+        DepositRequest {
+            pubkey: FixedBytes::repeat_byte(0x11),
+            withdrawal_credentials: B256::repeat_byte(0x22),
+            amount: 32_000_000_000,
+            signature: FixedBytes::repeat_byte(0x33),
+            index,
+        }
+    }
+
+    fn deposit_log(deposit: &DepositRequest) -> Log {
+        let event = DepositEvent {
+            pubkey: deposit.pubkey.to_vec().into(),
+            withdrawal_credentials: deposit.withdrawal_credentials.to_vec().into(),
+            amount: deposit.amount.to_le_bytes().to_vec().into(),
+            signature: deposit.signature.to_vec().into(),
+            index: deposit.index.to_le_bytes().to_vec().into(),
+        };
+        Log {
+            address: GNOSIS_DEPOSIT_CONTRACT_ADDRESS,
+            data: event.encode_log_data(),
+        }
+    }
+
+    fn receipt(logs: Vec<Log>) -> ReceiptWithBloom<Receipt<Log>> {
+        Receipt {
+            status: true.into(),
+            cumulative_gas_used: 21_000,
+            logs,
+        }
+        .into()
+    }
+
+    #[test]
+    fn test_deposit_logs() {
+        let other = Log {
+            address: Address::with_last_byte(1),
+            data: LogData::new_unchecked(vec![B256::ZERO], Bytes::new()),
+        };
+        let foreign_deposit = Log {
+            address: Address::with_last_byte(1),
+            ..deposit_log(&deposit(9))
+        };
+        let receipts = [
+            receipt(vec![deposit_log(&deposit(0)), other]),
+            receipt(vec![]),
+            receipt(vec![foreign_deposit, deposit_log(&deposit(1))]),
+        ];
+        assert_eq!(
+            deposit_requests(GNOSIS_DEPOSIT_CONTRACT_ADDRESS, &receipts),
+            Ok(vec![deposit(0), deposit(1)])
+        );
+        let requests =
+            GnosisRequests::collect(GNOSIS_DEPOSIT_CONTRACT_ADDRESS, &receipts, &[], &[]).unwrap();
+        assert_eq!(requests.deposits.len(), 2);
+        assert!(requests.withdrawals.is_empty());
+
+        let mut event = DepositEvent::decode_log_data(&deposit_log(&deposit(0)).data).unwrap();
+        event.amount = vec![1; 4].into();
+        let log = Log {
+            address: GNOSIS_DEPOSIT_CONTRACT_ADDRESS,
+            data: event.encode_log_data(),
+        };
+        assert_eq!(
+            parse_deposit_log(GNOSIS_DEPOSIT_CONTRACT_ADDRESS, &log),
+            Err(RequestsError::InvalidDepositLog(
+                "amount is 4 bytes".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_system_call_outputs() {
+        let withdrawal = WithdrawalRequest {
+            source_address: Address::repeat_byte(0xaa),
+            validator_pubkey: FixedBytes::repeat_byte(0xbb),
+            amount: 0x0102,
+        };
+        let output = [
+            encode_withdrawal(&withdrawal),
+            encode_withdrawal(&withdrawal),
+        ]
+        .concat();
+        assert_eq!(output[WITHDRAWAL_REQUEST_SIZE - 2..][..2], [1, 2]);
+        assert_eq!(
+            decode_withdrawal_requests(&output),
+            Ok(vec![withdrawal, withdrawal])
+        );
+        assert_eq!(
+            decode_withdrawal_requests(&output[1..]),
+            Err(RequestsError::InvalidOutputLength {
+                len: 2 * WITHDRAWAL_REQUEST_SIZE - 1,
+                size: WITHDRAWAL_REQUEST_SIZE,
+            })
+        );
+
+        let consolidation = ConsolidationRequest {
+            source_address: Address::repeat_byte(0xaa),
+            source_pubkey: FixedBytes::repeat_byte(0xbb),
+            target_pubkey: FixedBytes::repeat_byte(0xcc),
+        };
+        assert_eq!(
+            decode_consolidation_requests(&encode_consolidation(&consolidation)),
+            Ok(vec![consolidation])
+        );
+        assert_eq!(decode_consolidation_requests(&[]), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_requests_hash() {
+        assert_eq!(
+            GnosisRequests::default().requests_hash(),
+            EMPTY_REQUESTS_HASH
+        );
+        assert!(GnosisRequests::default().encode().is_empty());
+
+        let requests = GnosisRequests {
+            deposits: vec![deposit(0), deposit(1)],
+            consolidations: vec![ConsolidationRequest::default()],
+            ..Default::default()
+        };
+        let encoded = requests.encode();
+        assert_eq!(encoded.len(), 2);
+        assert_eq!(
+            encoded.iter().next().unwrap().len(),
+            1 + 2 * DEPOSIT_REQUEST_SIZE
+        );
+
+        let deposits = [
+            &[DEPOSIT_REQUEST_TYPE][..],
+            &encode_deposit(&deposit(0)),
+            &encode_deposit(&deposit(1)),
+        ]
+        .concat();
+        let consolidations = [
+            &[CONSOLIDATION_REQUEST_TYPE][..],
+            &[0; CONSOLIDATION_REQUEST_SIZE],
+        ]
+        .concat();
+        let expected =
+            Sha256::digest([Sha256::digest(deposits), Sha256::digest(consolidations)].concat());
+        assert_eq!(requests.requests_hash(), B256::from_slice(&expected));
+
+        let mut header = GnosisHeader::default();
+        assert_eq!(
+            verify_requests_hash(&header, &requests),
+            Err(RequestsError::MissingRequestsHash)
+        );
+        header.requests_hash = Some(requests.requests_hash());
+        assert_eq!(verify_requests_hash(&header, &requests), Ok(()));
+        assert!(matches!(
+            verify_requests_hash(&header, &GnosisRequests::default()),
+            Err(RequestsError::HashMismatch { .. })
+        ));
+    }
+}
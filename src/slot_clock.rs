@@ -0,0 +1,203 @@
+use alloy_primitives::BlockNumber;
+
+use crate::header::GnosisHeader;
+
+/// Length of a beacon chain slot on Gnosis, in seconds.
+pub const SECONDS_PER_SLOT: u64 = 5;
+
+/// Number of slots in a beacon chain epoch on Gnosis.
+pub const SLOTS_PER_EPOCH: u64 = 16;
+
+/// Genesis time of the Gnosis beacon chain.
+pub const GNOSIS_BEACON_GENESIS_TIME: u64 = 1_638_993_340;
+
+/// Genesis time of the Chiado beacon chain.
+pub const CHIADO_BEACON_GENESIS_TIME: u64 = 1_665_396_300;
+
+/// Errors returned when mapping a timestamp or header to a beacon slot.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum SlotError {
+    /// The timestamp is before the beacon chain genesis.
+    #[display("timestamp {timestamp} is before the beacon genesis at {genesis_time}")]
+    BeforeGenesis {
+        /// The timestamp.
+        timestamp: u64,
+        /// Genesis time of the beacon chain.
+        genesis_time: u64,
+    },
+    /// The timestamp is not the start of a slot.
+    #[display("timestamp {_0} is not on a slot boundary")]
+    NotOnSlotBoundary(#[error(not(source))] u64),
+    /// The header was sealed by AuRa and has no beacon slot.
+    #[display("block {_0} is pre-merge")]
+    PreMerge(#[error(not(source))] BlockNumber),
+}
+
+/// Maps between execution timestamps and beacon chain slots and epochs.
+///
+/// Every post-merge block is produced for a slot, and its timestamp is the start of that slot:
+/// `genesis_time + slot * SECONDS_PER_SLOT`. Slots without a block are skipped, so consecutive
+/// blocks may be several slots apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotClock {
+    /// Genesis time of the beacon chain.
+    pub genesis_time: u64,
+    /// Length of a slot, in seconds.
+    pub seconds_per_slot: u64,
+    /// Number of slots in an epoch.
+    pub slots_per_epoch: u64,
+}
+
+impl SlotClock {
+    /// Creates a clock with the Gnosis slot and epoch lengths.
+    pub const fn new(genesis_time: u64) -> Self {
+        Self {
+            genesis_time,
+            seconds_per_slot: SECONDS_PER_SLOT,
+            slots_per_epoch: SLOTS_PER_EPOCH,
+        }
+    }
+
+    /// Returns the clock of the Gnosis beacon chain.
+    pub const fn gnosis() -> Self {
+        Self::new(GNOSIS_BEACON_GENESIS_TIME)
+    }
+
+    /// Returns the clock of the Chiado beacon chain.
+    pub const fn chiado() -> Self {
+        Self::new(CHIADO_BEACON_GENESIS_TIME)
+    }
+
+    /// Returns the clock for a known Gnosis chain id.
+    pub const fn for_chain(chain_id: u64) -> Option<Self> {
+        match chain_id {
+            100 => Some(Self::gnosis()),
+            10200 => Some(Self::chiado()),
+            _ => None,
+        }
+    }
+
+    /// Returns the slot in progress at `timestamp`.
+    pub fn slot_containing(&self, timestamp: u64) -> Result<u64, SlotError> {
+        let elapsed = timestamp
+            .checked_sub(self.genesis_time)
+            .ok_or(SlotError::BeforeGenesis {
+                timestamp,
+                genesis_time: self.genesis_time,
+            })?;
+        Ok(elapsed / self.seconds_per_slot)
+    }
+
+    /// Returns the slot starting at `timestamp`, failing if `timestamp` is within a slot.
+    pub fn slot_at(&self, timestamp: u64) -> Result<u64, SlotError> {
+        let slot = self.slot_containing(timestamp)?;
+        if self.slot_timestamp(slot) != Some(timestamp) {
+            return Err(SlotError::NotOnSlotBoundary(timestamp));
+        }
+        Ok(slot)
+    }
+
+    /// Returns the start of `slot`, or `None` if it does not fit in a `u64`.
+    pub fn slot_timestamp(&self, slot: u64) -> Option<u64> {
+        slot.checked_mul(self.seconds_per_slot)?
+            .checked_add(self.genesis_time)
+    }
+
+    /// Returns the epoch of `slot`.
+    pub const fn epoch(&self, slot: u64) -> u64 {
+        slot / self.slots_per_epoch
+    }
+
+    /// Returns the first slot of `epoch`, or `None` if it does not fit in a `u64`.
+    pub const fn epoch_start_slot(&self, epoch: u64) -> Option<u64> {
+        epoch.checked_mul(self.slots_per_epoch)
+    }
+
+    /// Returns the start of `epoch`, or `None` if it does not fit in a `u64`.
+    pub fn epoch_timestamp(&self, epoch: u64) -> Option<u64> {
+        self.slot_timestamp(self.epoch_start_slot(epoch)?)
+    }
+
+    /// Returns the slot a post-merge header was produced for.
+    ///
+    /// Fails for AuRa headers and for timestamps that are not the start of a slot, which no
+    /// valid post-merge header has.
+    pub fn slot_for_header(&self, header: &GnosisHeader) -> Result<u64, SlotError> {
+        if header.is_pre_merge() {
+            return Err(SlotError::PreMerge(header.number));
+        }
+        self.slot_at(header.timestamp)
+    }
+
+    /// Returns the epoch a post-merge header was produced in.
+    pub fn epoch_for_header(&self, header: &GnosisHeader) -> Result<u64, SlotError> {
+        self.slot_for_header(header).map(|slot| self.epoch(slot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestChainGenerator;
+
+    #[test]
+    fn test_fork_epochs() {
+        // Capella and Deneb activate at the Shanghai and Cancun timestamps
+        let gnosis = SlotClock::gnosis();
+        assert_eq!(gnosis.epoch_timestamp(648_704), Some(1_690_889_660));
+        assert_eq!(gnosis.epoch_timestamp(889_856), Some(1_710_181_820));
+        let slot = gnosis.slot_at(1_710_181_820).unwrap();
+        assert_eq!((slot, gnosis.epoch(slot)), (889_856 * 16, 889_856));
+
+        let chiado = SlotClock::for_chain(10200).unwrap();
+        assert_eq!(chiado.epoch_timestamp(244_224), Some(1_684_934_220));
+        assert_eq!(SlotClock::for_chain(1), None);
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let clock = SlotClock::new(1_000);
+        assert_eq!(clock.slot_at(1_000), Ok(0));
+        assert_eq!(clock.slot_at(1_085), Ok(17));
+        assert_eq!(clock.epoch(17), 1);
+        assert_eq!(clock.slot_containing(1_087), Ok(17));
+        assert_eq!(
+            clock.slot_at(1_087),
+            Err(SlotError::NotOnSlotBoundary(1_087))
+        );
+        assert_eq!(
+            clock.slot_containing(999),
+            Err(SlotError::BeforeGenesis {
+                timestamp: 999,
+                genesis_time: 1_000
+            })
+        );
+        assert_eq!(clock.slot_timestamp(u64::MAX), None);
+        assert_eq!(clock.epoch_start_slot(3), Some(48));
+    }
+
+    #[test]
+    fn test_headers() {
+        let headers = TestChainGenerator::default().headers(18);
+        let aura = headers.iter().find(|header| header.is_pre_merge()).unwrap();
+        let merged = headers
+            .iter()
+            .find(|header| header.is_post_merge())
+            .unwrap();
+
+        let clock = SlotClock::new(merged.timestamp - 5 * SLOTS_PER_EPOCH * SECONDS_PER_SLOT);
+        assert_eq!(clock.slot_for_header(merged), Ok(5 * SLOTS_PER_EPOCH));
+        assert_eq!(clock.epoch_for_header(merged), Ok(5));
+        assert_eq!(
+            clock.slot_for_header(aura),
+            Err(SlotError::PreMerge(aura.number))
+        );
+
+        let mut misaligned = merged.clone();
+        misaligned.timestamp += 1;
+        assert_eq!(
+            clock.slot_for_header(&misaligned),
+            Err(SlotError::NotOnSlotBoundary(misaligned.timestamp))
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use std::ops::Range;
+
+use alloy_primitives::{BlockNumber, U256};
+
+use crate::header::GnosisHeader;
+
+/// Step duration of Gnosis Chain mainnet and Chiado, in seconds.
+pub const GNOSIS_STEP_DURATION: u64 = 5;
+
+/// Errors returned when building a [`StepSchedule`].
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum StepScheduleError {
+    /// No duration applies from timestamp zero.
+    #[display("no step duration at timestamp 0")]
+    MissingGenesisDuration,
+    /// A duration of zero was configured.
+    #[display("zero step duration at timestamp {_0}")]
+    ZeroDuration(#[error(not(source))] u64),
+    /// A transition is too far in the future to be represented.
+    #[display("step transition at timestamp {_0} overflows")]
+    Overflow(#[error(not(source))] u64),
+}
+
+/// Errors returned when checking the step of a pre-merge header.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum StepError {
+    /// The header has no AuRa seal.
+    #[display("block {_0} is not sealed by AuRa")]
+    NotAuRa(#[error(not(source))] BlockNumber),
+    /// The step does not fit in a `u64`.
+    #[display("block {block} has step {step} beyond any timestamp")]
+    StepTooLarge {
+        /// The block number.
+        block: BlockNumber,
+        /// The AuRa step of the header.
+        step: U256,
+    },
+    /// The timestamp is not within the window of the step.
+    #[display(
+        "block {block} has timestamp {timestamp}, outside the window {window:?} of step {step}"
+    )]
+    TimestampOutsideStep {
+        /// The block number.
+        block: BlockNumber,
+        /// The AuRa step of the header.
+        step: u64,
+        /// The timestamp of the header.
+        timestamp: u64,
+        /// The timestamps belonging to the step.
+        window: Range<u64>,
+    },
+    /// The step is not after the step of the parent.
+    #[display("block {block} has step {step}, not after its parent's step {parent_step}")]
+    StepNotIncreasing {
+        /// The block number.
+        block: BlockNumber,
+        /// The AuRa step of the parent.
+        parent_step: u64,
+        /// The AuRa step of the header.
+        step: u64,
+    },
+}
+
+/// A change of the step duration, aligned to a step boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepTransition {
+    /// First step with the new duration.
+    pub step: u64,
+    /// Start of that step.
+    pub timestamp: u64,
+    /// Duration of every step from here on, in seconds.
+    pub duration: u64,
+}
+
+/// Skipped steps between a block and its parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StepGap {
+    /// The block sealed after the gap.
+    pub block: BlockNumber,
+    /// The AuRa step of its parent.
+    pub parent_step: u64,
+    /// The AuRa step of the block.
+    pub step: u64,
+}
+
+impl StepGap {
+    /// Returns the number of steps in which no block was sealed.
+    pub const fn skipped(&self) -> u64 {
+        self.step - self.parent_step - 1
+    }
+}
+
+/// Maps AuRa steps to unix time, for step durations that change over time.
+///
+/// Durations are configured by the timestamp from which they apply, as in the `stepDuration` map
+/// of a chain spec. Like OpenEthereum and Nethermind, a new duration only takes effect at the
+/// first step boundary at or after its configured timestamp, so that steps never overlap. Step
+/// `s` covers the timestamps `start(s)..start(s + 1)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepSchedule {
+    /// Aligned transitions, the first at step and timestamp zero.
+    transitions: Vec<StepTransition>,
+}
+
+impl Default for StepSchedule {
+    fn default() -> Self {
+        Self::fixed(GNOSIS_STEP_DURATION)
+    }
+}
+
+impl StepSchedule {
+    /// Creates the schedule from `(timestamp, duration)` pairs.
+    ///
+    /// Pairs don't need to be sorted, but one of them must start at timestamp zero.
+    pub fn new(durations: impl IntoIterator<Item = (u64, u64)>) -> Result<Self, StepScheduleError> {
+        let mut durations: Vec<_> = durations.into_iter().collect();
+        durations.sort_unstable();
+        durations.dedup_by_key(|(timestamp, _)| *timestamp);
+
+        let mut durations = durations.into_iter();
+        let (_, duration) = durations
+            .next()
+            .filter(|(timestamp, _)| *timestamp == 0)
+            .ok_or(StepScheduleError::MissingGenesisDuration)?;
+        if duration == 0 {
+            return Err(StepScheduleError::ZeroDuration(0));
+        }
+        let mut transitions = vec![StepTransition {
+            step: 0,
+            timestamp: 0,
+            duration,
+        }];
+        for (timestamp, duration) in durations {
+            if duration == 0 {
+                return Err(StepScheduleError::ZeroDuration(timestamp));
+            }
+            let previous = transitions[transitions.len() - 1];
+            // Steps of the previous duration started before `timestamp`, rounded up
+            let steps = timestamp
+                .saturating_sub(previous.timestamp)
+                .div_ceil(previous.duration);
+            if steps == 0 {
+                // The previous duration would not have lasted a single step
+                transitions.last_mut().expect("not empty").duration = duration;
+                continue;
+            }
+            let aligned = || {
+                Some(StepTransition {
+                    step: previous.step.checked_add(steps)?,
+                    timestamp: previous
+                        .timestamp
+                        .checked_add(steps.checked_mul(previous.duration)?)?,
+                    duration,
+                })
+            };
+            transitions.push(aligned().ok_or(StepScheduleError::Overflow(timestamp))?);
+        }
+        Ok(Self { transitions })
+    }
+
+    /// Creates a schedule with a single duration.
+    ///
+    /// # Panics
+    ///
+    /// If `duration` is zero.
+    pub fn fixed(duration: u64) -> Self {
+        Self::new([(0, duration)]).expect("step duration is not zero")
+    }
+
+    /// Returns the step schedule of Gnosis Chain mainnet.
+    pub fn gnosis() -> Self {
+        Self::fixed(GNOSIS_STEP_DURATION)
+    }
+
+    /// Returns the step schedule of the Chiado testnet.
+    pub fn chiado() -> Self {
+        Self::fixed(GNOSIS_STEP_DURATION)
+    }
+
+    /// Returns the step schedule for a known Gnosis chain id.
+    pub fn for_chain(chain_id: u64) -> Option<Self> {
+        match chain_id {
+            100 => Some(Self::gnosis()),
+            10200 => Some(Self::chiado()),
+            _ => None,
+        }
+    }
+
+    /// Returns the aligned transitions, ordered by step.
+    pub fn transitions(&self) -> &[StepTransition] {
+        &self.transitions
+    }
+
+    /// Returns the step in progress at `timestamp`.
+    pub fn step_at(&self, timestamp: u64) -> u64 {
+        let transition = self
+            .transitions
+            .iter()
+            .rev()
+            .find(|transition| transition.timestamp <= timestamp)
+            .expect("first transition is at timestamp 0");
+        transition.step + (timestamp - transition.timestamp) / transition.duration
+    }
+
+    /// Returns the duration of `step`, in seconds.
+    pub fn step_duration(&self, step: u64) -> u64 {
+        self.transition_of(step).duration
+    }
+
+    /// Returns the start of `step`, or `None` if it does not fit in a `u64`.
+    pub fn step_start(&self, step: u64) -> Option<u64> {
+        let transition = self.transition_of(step);
+        (step - transition.step)
+            .checked_mul(transition.duration)?
+            .checked_add(transition.timestamp)
+    }
+
+    /// Returns the timestamps belonging to `step`, or `None` if they do not fit in a `u64`.
+    pub fn step_window(&self, step: u64) -> Option<Range<u64>> {
+        let start = self.step_start(step)?;
+        Some(start..start.checked_add(self.step_duration(step))?)
+    }
+
+    /// Checks that the timestamp of a pre-merge header is within the window of its step, and
+    /// returns the step.
+    pub fn verify_header(&self, header: &GnosisHeader) -> Result<u64, StepError> {
+        let step = header_step(header)?;
+        let window = self.step_window(step).ok_or(StepError::StepTooLarge {
+            block: header.number,
+            step: U256::from(step),
+        })?;
+        if !window.contains(&header.timestamp) {
+            return Err(StepError::TimestampOutsideStep {
+                block: header.number,
+                step,
+                timestamp: header.timestamp,
+                window,
+            });
+        }
+        Ok(step)
+    }
+
+    /// Checks a run of consecutive pre-merge headers and returns the gaps between them.
+    ///
+    /// Every header must be within its step window and sealed at a later step than the header
+    /// before it. Blocks sealed at the step right after their parent leave no gap.
+    pub fn step_gaps<'a>(
+        &self,
+        headers: impl IntoIterator<Item = &'a GnosisHeader>,
+    ) -> Result<Vec<StepGap>, StepError> {
+        let mut gaps = Vec::new();
+        let mut parent_step = None;
+        for header in headers {
+            let step = self.verify_header(header)?;
+            if let Some(parent_step) = parent_step {
+                if step <= parent_step {
+                    return Err(StepError::StepNotIncreasing {
+                        block: header.number,
+                        parent_step,
+                        step,
+                    });
+                }
+                if step > parent_step + 1 {
+                    gaps.push(StepGap {
+                        block: header.number,
+                        parent_step,
+                        step,
+                    });
+                }
+            }
+            parent_step = Some(step);
+        }
+        Ok(gaps)
+    }
+
+    /// Returns the transition in effect at `step`.
+    fn transition_of(&self, step: u64) -> &StepTransition {
+        self.transitions
+            .iter()
+            .rev()
+            .find(|transition| transition.step <= step)
+            .expect("first transition is at step 0")
+    }
+}
+
+/// Returns the AuRa step of a header as a `u64`.
+fn header_step(header: &GnosisHeader) -> Result<u64, StepError> {
+    let step = header.aura_step.ok_or(StepError::NotAuRa(header.number))?;
+    u64::try_from(step).map_err(|_| StepError::StepTooLarge {
+        block: header.number,
+        step,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestChainGenerator;
+
+    fn header(number: BlockNumber, step: u64, timestamp: u64) -> GnosisHeader {
+        GnosisHeader {
+            number,
+            timestamp,
+            aura_step: Some(U256::from(step)),
+            aura_seal: Some(Default::default()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_transitions_align_to_steps() {
+        // 5 second steps until 12, rounded up to 15, then 3 second steps
+        let schedule = StepSchedule::new([(12, 3), (0, 5)]).unwrap();
+        assert_eq!(
+            schedule.transitions()[1],
+            StepTransition {
+                step: 3,
+                timestamp: 15,
+                duration: 3
+            }
+        );
+        assert_eq!(schedule.step_at(14), 2);
+        assert_eq!(schedule.step_at(15), 3);
+        assert_eq!(schedule.step_at(20), 4);
+        assert_eq!(schedule.step_start(2), Some(10));
+        assert_eq!(schedule.step_start(4), Some(18));
+        assert_eq!(schedule.step_window(2), Some(10..15));
+        assert_eq!(schedule.step_duration(4), 3);
+        assert_eq!(
+            StepSchedule::new([(0, 5), (12, 3), (14, 4)])
+                .unwrap()
+                .transitions()[1..],
+            [StepTransition {
+                step: 3,
+                timestamp: 15,
+                duration: 4
+            }]
+        );
+
+        for timestamp in 0..100 {
+            let step = schedule.step_at(timestamp);
+            assert!(schedule.step_window(step).unwrap().contains(&timestamp));
+        }
+
+        let schedule = StepSchedule::for_chain(100).unwrap();
+        assert_eq!(schedule.step_at(1_000_000_003), 200_000_000);
+    }
+
+    #[test]
+    fn test_invalid_schedules() {
+        assert_eq!(
+            StepSchedule::new([(5, 5)]),
+            Err(StepScheduleError::MissingGenesisDuration)
+        );
+        assert_eq!(
+            StepSchedule::new([(0, 5), (100, 0)]),
+            Err(StepScheduleError::ZeroDuration(100))
+        );
+        assert_eq!(
+            StepSchedule::new([(0, 2), (u64::MAX, 1)]),
+            Err(StepScheduleError::Overflow(u64::MAX))
+        );
+    }
+
+    #[test]
+    fn test_verify_headers() {
+        let mut headers = TestChainGenerator::default().headers(10);
+        headers.retain(GnosisHeader::is_pre_merge);
+        assert!(headers.len() > 1);
+        assert_eq!(StepSchedule::gnosis().step_gaps(&headers), Ok(vec![]));
+
+        let schedule = StepSchedule::gnosis();
+        assert_eq!(schedule.verify_header(&header(1, 2, 14)), Ok(2));
+        assert_eq!(
+            schedule.verify_header(&header(1, 2, 15)),
+            Err(StepError::TimestampOutsideStep {
+                block: 1,
+                step: 2,
+                timestamp: 15,
+                window: 10..15
+            })
+        );
+        assert_eq!(
+            schedule.verify_header(&GnosisHeader::default()),
+            Err(StepError::NotAuRa(0))
+        );
+        let mut far = header(1, 0, 0);
+        far.aura_step = Some(U256::MAX);
+        assert!(matches!(
+            schedule.verify_header(&far),
+            Err(StepError::StepTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_step_gaps() {
+        let schedule = StepSchedule::gnosis();
+        let headers = [header(1, 10, 50), header(2, 11, 55), header(3, 14, 70)];
+        let gaps = schedule.step_gaps(&headers).unwrap();
+        assert_eq!(
+            gaps,
+            vec![StepGap {
+                block: 3,
+                parent_step: 11,
+                step: 14
+            }]
+        );
+        assert_eq!(gaps[0].skipped(), 2);
+
+        let headers = [header(1, 10, 50), header(2, 10, 52)];
+        assert_eq!(
+            schedule.step_gaps(&headers),
+            Err(StepError::StepNotIncreasing {
+                block: 2,
+                parent_step: 10,
+                step: 10
+            })
+        );
+    }
+}
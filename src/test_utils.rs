@@ -0,0 +1,519 @@
+use alloy_consensus::{
+    BlockBody, EMPTY_OMMER_ROOT_HASH, Eip658Value, Receipt, ReceiptEnvelope, ReceiptWithBloom,
+    SignableTransaction, Signed, TxEip1559, TxEip4844, TxEnvelope,
+    proofs::{calculate_receipt_root, calculate_transaction_root},
+};
+use alloy_eips::{
+    eip4844::DATA_GAS_PER_BLOB,
+    eip4895::{Withdrawal, Withdrawals},
+    eip7685::EMPTY_REQUESTS_HASH,
+};
+use alloy_primitives::{
+    Address, B64, B256, BlockNumber, Bloom, Bytes, FixedBytes, Signature, TxKind, U256, keccak256,
+};
+use reth_chainspec::BaseFeeParams;
+
+use crate::{
+    aura::{self, SigningKey},
+    blob_schedule::{BlobSchedule, GNOSIS_BLOB_PARAMS},
+    header::GnosisHeader,
+    withdrawals::withdrawals_root,
+};
+
+/// Gas used by every generated transaction, a plain transfer.
+const TRANSFER_GAS: u64 = 21_000;
+
+/// Base fee of the genesis block. London is active from genesis, as on Chiado.
+const GENESIS_BASE_FEE: u64 = 1_000_000_000;
+
+/// Difficulty of the genesis block of Gnosis Chain.
+const GENESIS_DIFFICULTY: u64 = 0x20000;
+
+/// Parameters of a generated test chain.
+///
+/// Fork timestamps only take effect on post-merge blocks, and each fork requires the previous one,
+/// like on Gnosis Chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestChainConfig {
+    /// Seed of every random value in the chain.
+    pub seed: u64,
+    /// Chain id of the generated transactions.
+    pub chain_id: u64,
+    /// Number of AuRa validators.
+    pub validators: usize,
+    /// AuRa step duration in seconds, also used as slot time after the merge.
+    pub step_duration: u64,
+    /// Timestamp of the genesis block.
+    pub genesis_timestamp: u64,
+    /// Gas limit of every block.
+    pub gas_limit: u64,
+    /// First proof-of-stake block.
+    pub merge_block: BlockNumber,
+    /// Shanghai activation timestamp.
+    pub shanghai_timestamp: Option<u64>,
+    /// Cancun activation timestamp.
+    pub cancun_timestamp: Option<u64>,
+    /// Prague activation timestamp.
+    pub prague_timestamp: Option<u64>,
+    /// Blob parameters by timestamp.
+    pub blob_schedule: BlobSchedule,
+    /// Whether to return block bodies along with the headers.
+    pub with_bodies: bool,
+}
+
+impl Default for TestChainConfig {
+    /// Three validators seal blocks 1 to 9, the merge happens at block 10, and Shanghai, Cancun
+    /// and Prague activate at blocks 12, 14 and 16.
+    fn default() -> Self {
+        let genesis_timestamp = 1_700_000_000;
+        let step_duration = 5;
+        let cancun_timestamp = genesis_timestamp + 14 * step_duration;
+        let prague_timestamp = genesis_timestamp + 16 * step_duration;
+        Self {
+            seed: 0,
+            chain_id: 100,
+            validators: 3,
+            step_duration,
+            genesis_timestamp,
+            gas_limit: 17_000_000,
+            merge_block: 10,
+            shanghai_timestamp: Some(genesis_timestamp + 12 * step_duration),
+            cancun_timestamp: Some(cancun_timestamp),
+            prague_timestamp: Some(prague_timestamp),
+            blob_schedule: BlobSchedule::new([
+                (cancun_timestamp, GNOSIS_BLOB_PARAMS),
+                (prague_timestamp, GNOSIS_BLOB_PARAMS),
+            ]),
+            with_bodies: false,
+        }
+    }
+}
+
+/// A generated block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestBlock {
+    /// The header.
+    pub header: GnosisHeader,
+    /// The body, if [`TestChainConfig::with_bodies`] is set.
+    pub body: Option<BlockBody<TxEnvelope, GnosisHeader>>,
+}
+
+/// Generates a deterministic, linked chain of Gnosis headers from a seed.
+///
+/// Pre-merge blocks are sealed by the validator owning their step and carry the AuRa difficulty.
+/// Every block contains a few transfers, and post-Cancun blocks a blob transaction, so that the
+/// base fee and excess blob gas move from block to block. Transaction, receipt and withdrawal
+/// roots match the generated bodies. State roots are random, there is no state.
+#[derive(Debug, Clone)]
+pub struct TestChainGenerator {
+    config: TestChainConfig,
+    validators: Vec<SigningKey>,
+    sender: SigningKey,
+    sender_nonce: u64,
+    withdrawal_index: u64,
+    rng: u64,
+    genesis: GnosisHeader,
+    parent: GnosisHeader,
+}
+
+impl Default for TestChainGenerator {
+    fn default() -> Self {
+        Self::new(TestChainConfig::default())
+    }
+}
+
+impl TestChainGenerator {
+    /// Creates the generator and the genesis block.
+    pub fn new(config: TestChainConfig) -> Self {
+        let key = |label: &str, index: usize| {
+            let secret = keccak256(
+                [
+                    &config.seed.to_be_bytes(),
+                    label.as_bytes(),
+                    &index.to_be_bytes(),
+                ]
+                .concat(),
+            );
+            SigningKey::from_slice(secret.as_slice()).expect("valid secret key")
+        };
+        let validators = (0..config.validators)
+            .map(|index| key("validator", index))
+            .collect();
+        let sender = key("sender", 0);
+
+        let mut genesis = GnosisHeader {
+            number: 0,
+            timestamp: config.genesis_timestamp,
+            gas_limit: config.gas_limit,
+            base_fee_per_gas: Some(GENESIS_BASE_FEE),
+            ..Default::default()
+        };
+        if config.merge_block == 0 {
+            genesis.mix_hash = Some(B256::ZERO);
+            genesis.nonce = Some(B64::ZERO);
+        } else {
+            genesis.difficulty = U256::from(GENESIS_DIFFICULTY);
+            genesis.aura_step = Some(U256::from(config.genesis_timestamp / config.step_duration));
+            genesis.aura_seal = Some(FixedBytes::ZERO);
+        }
+
+        Self {
+            rng: config.seed,
+            config,
+            validators,
+            sender,
+            sender_nonce: 0,
+            withdrawal_index: 0,
+            parent: genesis.clone(),
+            genesis,
+        }
+    }
+
+    /// Returns the configuration.
+    pub const fn config(&self) -> &TestChainConfig {
+        &self.config
+    }
+
+    /// Returns the genesis header.
+    pub const fn genesis(&self) -> &GnosisHeader {
+        &self.genesis
+    }
+
+    /// Returns the AuRa validator set, in sealing order.
+    pub fn validators(&self) -> Vec<Address> {
+        self.validators
+            .iter()
+            .map(Address::from_private_key)
+            .collect()
+    }
+
+    /// Generates the next `count` blocks.
+    pub fn blocks(&mut self, count: usize) -> Vec<TestBlock> {
+        (0..count).map(|_| self.next_block()).collect()
+    }
+
+    /// Generates the next `count` blocks and returns their headers.
+    pub fn headers(&mut self, count: usize) -> Vec<GnosisHeader> {
+        (0..count).map(|_| self.next_block().header).collect()
+    }
+
+    /// Generates the next block on top of the last generated one.
+    pub fn next_block(&mut self) -> TestBlock {
+        let number = self.parent.number + 1;
+        let timestamp = self.parent.timestamp + self.config.step_duration;
+        let post_merge = number >= self.config.merge_block;
+        let active = |fork: Option<u64>| fork.is_some_and(|fork| timestamp >= fork);
+        let shanghai = post_merge && active(self.config.shanghai_timestamp);
+        let cancun = shanghai && active(self.config.cancun_timestamp);
+        let prague = cancun && active(self.config.prague_timestamp);
+
+        let base_fee = self
+            .parent
+            .next_block_base_fee(BaseFeeParams::ethereum())
+            .unwrap_or(GENESIS_BASE_FEE);
+
+        let mut transactions = Vec::new();
+        for _ in 0..self.next_u64() % 4 {
+            transactions.push(self.transfer(base_fee));
+        }
+        let mut blob_gas_used = None;
+        let mut excess_blob_gas = None;
+        if cancun {
+            let params = self
+                .config
+                .blob_schedule
+                .params_at_timestamp(timestamp)
+                .expect("blob parameters scheduled at Cancun");
+            let max_blobs = params.max_blob_count.min(params.max_blobs_per_tx);
+            let blobs = self.next_u64() % (max_blobs + 1);
+            if blobs > 0 {
+                transactions.push(self.blob_transaction(base_fee, blobs));
+            }
+            blob_gas_used = Some(blobs * DATA_GAS_PER_BLOB);
+            excess_blob_gas = Some(
+                self.parent
+                    .scheduled_next_block_excess_blob_gas(&self.config.blob_schedule, timestamp)
+                    .unwrap_or_default(),
+            );
+        }
+
+        let receipts: Vec<ReceiptEnvelope> = transactions
+            .iter()
+            .scan(0, |cumulative_gas_used, tx: &TxEnvelope| {
+                *cumulative_gas_used += TRANSFER_GAS;
+                let receipt = ReceiptWithBloom {
+                    receipt: Receipt {
+                        status: Eip658Value::Eip658(true),
+                        cumulative_gas_used: *cumulative_gas_used,
+                        logs: vec![],
+                    },
+                    logs_bloom: Bloom::ZERO,
+                };
+                Some(match tx {
+                    TxEnvelope::Eip4844(_) => ReceiptEnvelope::Eip4844(receipt),
+                    _ => ReceiptEnvelope::Eip1559(receipt),
+                })
+            })
+            .collect();
+
+        let withdrawals = shanghai.then(|| {
+            (0..self.next_u64() % 3)
+                .map(|_| self.withdrawal())
+                .collect::<Vec<_>>()
+        });
+
+        let mut header = GnosisHeader {
+            parent_hash: self.parent.hash_slow(),
+            ommers_hash: EMPTY_OMMER_ROOT_HASH,
+            state_root: self.next_b256(),
+            transactions_root: calculate_transaction_root(&transactions),
+            receipts_root: calculate_receipt_root(&receipts),
+            number,
+            gas_limit: self.config.gas_limit,
+            gas_used: TRANSFER_GAS * transactions.len() as u64,
+            timestamp,
+            base_fee_per_gas: Some(base_fee),
+            withdrawals_root: withdrawals.as_deref().map(withdrawals_root),
+            blob_gas_used,
+            excess_blob_gas,
+            parent_beacon_block_root: cancun.then(|| self.next_b256()),
+            requests_hash: prague.then_some(EMPTY_REQUESTS_HASH),
+            ..Default::default()
+        };
+
+        if post_merge {
+            header.beneficiary = Address::from_word(self.next_b256());
+            header.mix_hash = Some(self.next_b256());
+            header.nonce = Some(B64::ZERO);
+        } else {
+            let step = U256::from(timestamp / self.config.step_duration);
+            let parent_step = self.parent.aura_step.unwrap_or_default();
+            header.difficulty = aura::block_difficulty(parent_step, step);
+            let proposer = (step % U256::from(self.validators.len())).to::<usize>();
+            header = aura::seal_header(header, step, &self.validators[proposer])
+                .expect("pre-merge header can be sealed");
+        }
+
+        self.parent = header.clone();
+        let body = self.config.with_bodies.then(|| BlockBody {
+            transactions,
+            ommers: vec![],
+            withdrawals: withdrawals.map(Withdrawals::new),
+        });
+        TestBlock { header, body }
+    }
+
+    fn transfer(&mut self, base_fee: u64) -> TxEnvelope {
+        let tx = TxEip1559 {
+            chain_id: self.config.chain_id,
+            nonce: self.sender_nonce,
+            gas_limit: TRANSFER_GAS,
+            max_fee_per_gas: u128::from(base_fee) * 2 + 1_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: TxKind::Call(Address::from_word(self.next_b256())),
+            value: U256::from(self.next_u64() % 1_000_000),
+            access_list: Default::default(),
+            input: Bytes::new(),
+        };
+        self.sign(tx)
+    }
+
+    fn blob_transaction(&mut self, base_fee: u64, blobs: u64) -> TxEnvelope {
+        let blob_versioned_hashes = (0..blobs)
+            .map(|_| {
+                let mut hash = self.next_b256();
+                hash[0] = 0x01;
+                hash
+            })
+            .collect();
+        let tx = TxEip4844 {
+            chain_id: self.config.chain_id,
+            nonce: self.sender_nonce,
+            gas_limit: TRANSFER_GAS,
+            max_fee_per_gas: u128::from(base_fee) * 2 + 1_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: Address::from_word(self.next_b256()),
+            value: U256::ZERO,
+            access_list: Default::default(),
+            blob_versioned_hashes,
+            max_fee_per_blob_gas: 100_000_000_000,
+            input: Bytes::new(),
+        };
+        self.sign(tx)
+    }
+
+    fn sign<T>(&mut self, tx: T) -> TxEnvelope
+    where
+        T: SignableTransaction<Signature>,
+        TxEnvelope: From<Signed<T>>,
+    {
+        let signature = self
+            .sender
+            .sign_prehash_recoverable(tx.signature_hash().as_slice())
+            .expect("transaction can be signed");
+        self.sender_nonce += 1;
+        tx.into_signed(Signature::from(signature)).into()
+    }
+
+    fn withdrawal(&mut self) -> Withdrawal {
+        let withdrawal = Withdrawal {
+            index: self.withdrawal_index,
+            validator_index: self.next_u64() % 1_000,
+            address: Address::from_word(self.next_b256()),
+            amount: self.next_u64() % 32_000_000_000,
+        };
+        self.withdrawal_index += 1;
+        withdrawal
+    }
+
+    /// Returns the next value of a SplitMix64 sequence.
+    fn next_u64(&mut self) -> u64 {
+        self.rng = self.rng.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.rng;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn next_b256(&mut self) -> B256 {
+        keccak256(self.next_u64().to_be_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aura::verify_seal;
+    use alloy_rlp::{Decodable, Encodable};
+
+    #[test]
+    fn test_chain_is_linked_and_deterministic() {
+        let mut generator = TestChainGenerator::new(TestChainConfig::default());
+        let blocks = generator.blocks(20);
+
+        let mut parent = generator.genesis().clone();
+        for block in &blocks {
+            assert_eq!(block.header.number, parent.number + 1);
+            assert_eq!(block.header.parent_hash, parent.hash_slow());
+            assert_eq!(
+                block.header.base_fee_per_gas,
+                parent.next_block_base_fee(BaseFeeParams::ethereum())
+            );
+            parent = block.header.clone();
+        }
+
+        let again = TestChainGenerator::default().blocks(20);
+        assert_eq!(again, blocks);
+        let headers = TestChainGenerator::default().headers(20);
+        assert!(headers.iter().eq(blocks.iter().map(|block| &block.header)));
+
+        let other = TestChainGenerator::new(TestChainConfig {
+            seed: 1,
+            ..Default::default()
+        })
+        .blocks(1);
+        assert_ne!(other[0].header.hash_slow(), blocks[0].header.hash_slow());
+    }
+
+    #[test]
+    fn test_aura_blocks_are_sealed() {
+        let mut generator = TestChainGenerator::new(TestChainConfig::default());
+        let validators = generator.validators();
+        let blocks = generator.blocks(9);
+        for block in &blocks {
+            let header = &block.header;
+            assert!(header.is_pre_merge());
+            assert!(verify_seal(header, &validators).is_ok());
+            assert_eq!(header.difficulty, U256::from(u128::MAX - 1));
+        }
+    }
+
+    #[test]
+    fn test_forks() {
+        let config = TestChainConfig::default();
+        let mut generator = TestChainGenerator::new(config.clone());
+        let blocks = generator.blocks(20);
+        let header = |number: usize| &blocks[number - 1].header;
+
+        assert!(header(9).is_pre_merge());
+        assert!(header(10).is_post_merge());
+        assert_eq!(header(10).difficulty, U256::ZERO);
+        assert!(!header(11).shanghai_active());
+        assert!(header(12).shanghai_active() && !header(12).cancun_active());
+        assert!(header(14).cancun_active() && !header(14).prague_active());
+        assert!(header(16).prague_active());
+
+        // Excess blob gas follows the schedule from block to block
+        for number in 15..=20 {
+            assert_eq!(
+                header(number).excess_blob_gas,
+                header(number - 1).scheduled_next_block_excess_blob_gas(
+                    &config.blob_schedule,
+                    header(number).timestamp
+                )
+            );
+            assert!(
+                header(number).blob_gas_used.unwrap()
+                    <= header(number)
+                        .max_blob_count(&config.blob_schedule)
+                        .unwrap()
+                        * DATA_GAS_PER_BLOB
+            );
+        }
+
+        for block in &blocks {
+            let mut encoded = Vec::new();
+            block.header.encode(&mut encoded);
+            assert_eq!(
+                GnosisHeader::decode(&mut encoded.as_slice()).unwrap(),
+                block.header
+            );
+        }
+    }
+
+    #[test]
+    fn test_bodies_match_headers() {
+        let mut generator = TestChainGenerator::new(TestChainConfig {
+            with_bodies: true,
+            ..Default::default()
+        });
+        for block in generator.blocks(20) {
+            let body = block.body.unwrap();
+            let header = block.header;
+            assert_eq!(
+                header.transactions_root,
+                calculate_transaction_root(&body.transactions)
+            );
+            assert_eq!(
+                header.gas_used,
+                TRANSFER_GAS * body.transactions.len() as u64
+            );
+            assert_eq!(
+                header.withdrawals_root,
+                body.withdrawals
+                    .as_ref()
+                    .map(|withdrawals| withdrawals_root(withdrawals))
+            );
+            let blobs: usize = body
+                .transactions
+                .iter()
+                .filter_map(|tx| tx.as_eip4844())
+                .map(|tx| tx.tx().tx().blob_versioned_hashes.len())
+                .sum();
+            assert_eq!(
+                header.blob_gas_used.unwrap_or_default(),
+                blobs as u64 * DATA_GAS_PER_BLOB
+            );
+        }
+    }
+
+    #[test]
+    fn test_post_merge_genesis() {
+        let mut generator = TestChainGenerator::new(TestChainConfig {
+            merge_block: 0,
+            ..Default::default()
+        });
+        assert!(generator.genesis().is_post_merge());
+        assert!(generator.next_block().header.is_post_merge());
+    }
+}
@@ -0,0 +1,743 @@
+use std::collections::BTreeMap;
+
+use alloy_consensus::TxReceipt;
+use alloy_eips::eip4788::SYSTEM_ADDRESS;
+use alloy_primitives::{Address, B256, BlockNumber, Bloom, Bytes, Log};
+use alloy_sol_types::{SolCall, SolEvent, sol};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    aura::{self, SealError},
+    header::GnosisHeader,
+    withdrawals::SYSTEM_CALL_GAS_LIMIT,
+};
+
+sol! {
+    /// Emitted by the validator set contract to signal a new validator set.
+    ///
+    /// `parentHash` is the parent hash of the block emitting the event.
+    event InitiateChange(bytes32 indexed parentHash, address[] newSet);
+
+    /// Called by the system once a signalled change is finalized.
+    function finalizeChange();
+
+    /// Returns the current validator set.
+    function getValidators() returns (address[] validators);
+}
+
+/// Returns the bloom a block must contain to possibly signal a validator set change.
+///
+/// This is the bloom of an `InitiateChange` log emitted by `contract` in a block with the given
+/// parent hash.
+pub fn initiate_change_bloom(contract: Address, parent_hash: B256) -> Bloom {
+    let mut bloom = Bloom::default();
+    bloom.accrue_raw_log(contract, &[InitiateChange::SIGNATURE_HASH, parent_hash]);
+    bloom
+}
+
+/// Returns `true` if the logs bloom of `header` allows for a validator set change signalled by
+/// `contract`.
+///
+/// This is a cheap filter: `false` means the block certainly doesn't signal a change, `true` means
+/// the receipts have to be checked with [`decode_initiate_change`].
+pub fn may_signal_change(header: &GnosisHeader, contract: Address) -> bool {
+    header
+        .logs_bloom
+        .contains(&initiate_change_bloom(contract, header.parent_hash))
+}
+
+/// Extracts the validator set signalled by `contract` in a block, if any.
+///
+/// Only logs whose parent hash topic matches the header are considered. If several matching logs
+/// are found, the first one is used, as OpenEthereum and Nethermind do.
+pub fn decode_initiate_change<R>(
+    header: &GnosisHeader,
+    contract: Address,
+    receipts: &[R],
+) -> Option<Vec<Address>>
+where
+    R: TxReceipt<Log = Log>,
+{
+    if !may_signal_change(header, contract) {
+        return None;
+    }
+    receipts
+        .iter()
+        .flat_map(|receipt| receipt.logs())
+        .filter(|log| log.address == contract)
+        .filter(|log| log.topics().get(1) == Some(&header.parent_hash))
+        .find_map(|log| InitiateChange::decode_log(log).ok())
+        .map(|log| log.data.newSet)
+}
+
+/// Builds the calldata of the `finalizeChange` system call.
+pub fn finalize_change_input() -> Bytes {
+    finalizeChangeCall {}.abi_encode().into()
+}
+
+/// The `finalizeChange` system call made to the validator set contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalizeChangeSystemCall {
+    /// Sender of the call.
+    pub caller: Address,
+    /// The validator set contract.
+    pub to: Address,
+    /// Gas limit of the call.
+    pub gas_limit: u64,
+    /// ABI encoded `finalizeChange` calldata.
+    pub input: Bytes,
+}
+
+impl FinalizeChangeSystemCall {
+    /// Builds the call for the given validator set contract.
+    pub fn new(contract: Address) -> Self {
+        Self {
+            caller: SYSTEM_ADDRESS,
+            to: contract,
+            gas_limit: SYSTEM_CALL_GAS_LIMIT,
+            input: finalize_change_input(),
+        }
+    }
+}
+
+/// A validator set change that has been signalled but not finalized yet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingChange {
+    /// Number of the block that emitted `InitiateChange`.
+    pub block_number: BlockNumber,
+    /// Hash of the block that emitted `InitiateChange`.
+    pub block_hash: B256,
+    /// The signalled validator set.
+    pub validators: Vec<Address>,
+}
+
+/// Errors returned by the [`ValidatorSetTracker`] and the [`MultiValidatorSet`].
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum ValidatorSetError {
+    /// Headers were not fed in order.
+    #[display("expected block {expected}, got {got}")]
+    OutOfOrder {
+        /// The next block number the tracker expects.
+        expected: BlockNumber,
+        /// The block number that was passed.
+        got: BlockNumber,
+    },
+    /// No validator source is configured for the block.
+    #[display("no validator source configured for block {_0}")]
+    NoSource(#[error(not(source))] BlockNumber),
+    /// The block is sealed by a contract whose tracking was not started.
+    #[display("validator contract {contract} activated at block {transition} is not tracked")]
+    UntrackedContract {
+        /// Block at which the contract was activated.
+        transition: BlockNumber,
+        /// The validator set contract.
+        contract: Address,
+    },
+    /// The transition does not activate a validator set contract.
+    #[display("no validator contract is activated at block {_0}")]
+    NotAContract(#[error(not(source))] BlockNumber),
+    /// The seal of the header is invalid.
+    #[display("{_0}")]
+    Seal(SealError),
+}
+
+/// Tracks the validator sets of a POSDAO validator set contract over a range of headers.
+///
+/// A change is signalled by an `InitiateChange` event and stays pending until the block that
+/// signalled it is finalized. The new set then seals every block after the one in which the
+/// finalization was observed, which is also the block where `finalizeChange` is called.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidatorSetTracker {
+    contract: Address,
+    /// Validator sets keyed by the first block they seal.
+    sets: BTreeMap<BlockNumber, Vec<Address>>,
+    pending: Option<PendingChange>,
+    next_block: BlockNumber,
+}
+
+impl ValidatorSetTracker {
+    /// Creates a tracker for `contract`, starting at `first_block` with the given validators.
+    pub fn new(contract: Address, first_block: BlockNumber, validators: Vec<Address>) -> Self {
+        Self {
+            contract,
+            sets: BTreeMap::from([(first_block, validators)]),
+            pending: None,
+            next_block: first_block,
+        }
+    }
+
+    /// Returns the tracked validator set contract.
+    pub const fn contract(&self) -> Address {
+        self.contract
+    }
+
+    /// Returns the change waiting for finality, if any.
+    pub const fn pending(&self) -> Option<&PendingChange> {
+        self.pending.as_ref()
+    }
+
+    /// Returns the validator set sealing blocks after the last processed one.
+    pub fn current(&self) -> &[Address] {
+        self.validators_at(self.next_block).unwrap_or_default()
+    }
+
+    /// Processes the next header and its receipts, recording any signalled change as pending.
+    ///
+    /// A newer signal replaces a pending change that was not finalized yet.
+    pub fn on_block<R>(
+        &mut self,
+        header: &GnosisHeader,
+        receipts: &[R],
+    ) -> Result<Option<&PendingChange>, ValidatorSetError>
+    where
+        R: TxReceipt<Log = Log>,
+    {
+        if header.number != self.next_block {
+            return Err(ValidatorSetError::OutOfOrder {
+                expected: self.next_block,
+                got: header.number,
+            });
+        }
+        self.next_block += 1;
+
+        let Some(validators) = decode_initiate_change(header, self.contract, receipts) else {
+            return Ok(None);
+        };
+        self.pending = Some(PendingChange {
+            block_number: header.number,
+            block_hash: header.hash_slow(),
+            validators,
+        });
+        Ok(self.pending.as_ref())
+    }
+
+    /// Applies finality up to `finalized`, activating the pending change if its signalling block
+    /// is now final.
+    ///
+    /// The new set takes effect from the next block to be processed. Returns the activated
+    /// change, if any.
+    pub fn on_finalized(&mut self, finalized: BlockNumber) -> Option<PendingChange> {
+        if self
+            .pending
+            .as_ref()
+            .is_none_or(|pending| pending.block_number > finalized)
+        {
+            return None;
+        }
+        let change = self.pending.take()?;
+        self.sets.insert(self.next_block, change.validators.clone());
+        Some(change)
+    }
+
+    /// Returns the validator set sealing the given block.
+    ///
+    /// Returns `None` for blocks before the tracker's first block.
+    pub fn validators_at(&self, block: BlockNumber) -> Option<&[Address]> {
+        self.sets
+            .range(..=block)
+            .next_back()
+            .map(|(_, validators)| validators.as_slice())
+    }
+
+    /// Returns the validator set that must have sealed `header`.
+    pub fn validators_for(&self, header: &GnosisHeader) -> Option<&[Address]> {
+        self.validators_at(header.number)
+    }
+
+    /// Returns every recorded set, keyed by the first block it seals.
+    pub fn sets(&self) -> &BTreeMap<BlockNumber, Vec<Address>> {
+        &self.sets
+    }
+}
+
+/// Where the validators of an AuRa block come from.
+///
+/// This mirrors the validator entries of OpenEthereum and Nethermind chain specs. Both contract
+/// kinds signal changes through `InitiateChange`, `Contract` additionally accepts misbehaviour
+/// reports, which does not matter for tracking the set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ValidatorSource {
+    /// A fixed list of validators.
+    List(Vec<Address>),
+    /// A validator set contract.
+    SafeContract(Address),
+    /// A validator set contract that also accepts reports.
+    Contract(Address),
+}
+
+impl ValidatorSource {
+    /// Returns the validator set contract, or `None` for a fixed list.
+    pub const fn contract(&self) -> Option<Address> {
+        match self {
+            Self::List(_) => None,
+            Self::SafeContract(contract) | Self::Contract(contract) => Some(*contract),
+        }
+    }
+}
+
+/// The `multi` validator configuration of an AuRa chain spec.
+///
+/// Each transition switches to a new [`ValidatorSource`] from the given block number. In JSON
+/// it is either a single source or a `multi` map keyed by decimal or `0x` prefixed hex block
+/// numbers, for example
+/// `{"multi": {"0": {"list": ["0x…"]}, "1300": {"safeContract": "0x…"}}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "ValidatorSetJson", into = "ValidatorSetJson")]
+pub struct ValidatorSetConfig {
+    transitions: BTreeMap<BlockNumber, ValidatorSource>,
+}
+
+impl ValidatorSetConfig {
+    /// Creates the configuration from `(transition block, source)` pairs.
+    pub fn new(transitions: impl IntoIterator<Item = (BlockNumber, ValidatorSource)>) -> Self {
+        Self {
+            transitions: transitions.into_iter().collect(),
+        }
+    }
+
+    /// Returns the transitions, ordered by block number.
+    pub fn transitions(&self) -> impl Iterator<Item = (BlockNumber, &ValidatorSource)> + '_ {
+        self.transitions
+            .iter()
+            .map(|(block, source)| (*block, source))
+    }
+
+    /// Returns the source sealing the given block, with the transition that activated it.
+    ///
+    /// Like OpenEthereum, which selects the last set with a transition at or below the parent
+    /// block number plus one, a transition at block `N` already applies to block `N`.
+    pub fn source_at(&self, block: BlockNumber) -> Option<(BlockNumber, &ValidatorSource)> {
+        self.transitions
+            .range(..=block)
+            .next_back()
+            .map(|(transition, source)| (*transition, source))
+    }
+
+    /// Returns the source that must have sealed `header`.
+    pub fn source_for_header(
+        &self,
+        header: &GnosisHeader,
+    ) -> Option<(BlockNumber, &ValidatorSource)> {
+        self.source_at(header.number)
+    }
+}
+
+/// JSON representation of a [`ValidatorSetConfig`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum ValidatorSetJson {
+    Multi {
+        multi: BTreeMap<String, ValidatorSource>,
+    },
+    Single(ValidatorSource),
+}
+
+impl TryFrom<ValidatorSetJson> for ValidatorSetConfig {
+    type Error = String;
+
+    fn try_from(json: ValidatorSetJson) -> Result<Self, Self::Error> {
+        match json {
+            ValidatorSetJson::Single(source) => Ok(Self::new([(0, source)])),
+            ValidatorSetJson::Multi { multi } => multi
+                .into_iter()
+                .map(|(key, source)| {
+                    let block = match key.strip_prefix("0x") {
+                        Some(hex) => u64::from_str_radix(hex, 16),
+                        None => key.parse(),
+                    }
+                    .map_err(|err| format!("invalid transition block {key:?}: {err}"))?;
+                    Ok((block, source))
+                })
+                .collect::<Result<_, String>>()
+                .map(|transitions| Self { transitions }),
+        }
+    }
+}
+
+impl From<ValidatorSetConfig> for ValidatorSetJson {
+    fn from(config: ValidatorSetConfig) -> Self {
+        Self::Multi {
+            multi: config
+                .transitions
+                .into_iter()
+                .map(|(block, source)| (block.to_string(), source))
+                .collect(),
+        }
+    }
+}
+
+/// Validator sets of a chain following a [`ValidatorSetConfig`], from genesis onward.
+///
+/// Fixed lists are answered from the configuration. Every contract transition gets its own
+/// [`ValidatorSetTracker`], which has to be started with the contract's initial validators, read
+/// with `getValidators()` on the state before the transition block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiValidatorSet {
+    config: ValidatorSetConfig,
+    /// Trackers keyed by the transition block of their contract.
+    trackers: BTreeMap<BlockNumber, ValidatorSetTracker>,
+}
+
+impl MultiValidatorSet {
+    /// Creates the validator sets for the given configuration.
+    pub fn new(config: ValidatorSetConfig) -> Self {
+        Self {
+            config,
+            trackers: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the configuration.
+    pub const fn config(&self) -> &ValidatorSetConfig {
+        &self.config
+    }
+
+    /// Starts tracking the contract activated at `transition`, with the validators sealing the
+    /// transition block.
+    pub fn start_contract(
+        &mut self,
+        transition: BlockNumber,
+        validators: Vec<Address>,
+    ) -> Result<&ValidatorSetTracker, ValidatorSetError> {
+        let contract = self
+            .config
+            .transitions
+            .get(&transition)
+            .and_then(ValidatorSource::contract)
+            .ok_or(ValidatorSetError::NotAContract(transition))?;
+        let tracker = ValidatorSetTracker::new(contract, transition, validators);
+        self.trackers.insert(transition, tracker);
+        Ok(&self.trackers[&transition])
+    }
+
+    /// Returns the tracker of the contract activated at `transition`, if it was started.
+    pub fn tracker(&self, transition: BlockNumber) -> Option<&ValidatorSetTracker> {
+        self.trackers.get(&transition)
+    }
+
+    /// Processes the next header and its receipts.
+    ///
+    /// Headers sealed by a contract are forwarded to its tracker, headers sealed by a fixed list
+    /// are ignored.
+    pub fn on_block<R>(
+        &mut self,
+        header: &GnosisHeader,
+        receipts: &[R],
+    ) -> Result<Option<&PendingChange>, ValidatorSetError>
+    where
+        R: TxReceipt<Log = Log>,
+    {
+        let (transition, source) = self
+            .config
+            .source_for_header(header)
+            .ok_or(ValidatorSetError::NoSource(header.number))?;
+        let Some(contract) = source.contract() else {
+            return Ok(None);
+        };
+        self.trackers
+            .get_mut(&transition)
+            .ok_or(ValidatorSetError::UntrackedContract {
+                transition,
+                contract,
+            })?
+            .on_block(header, receipts)
+    }
+
+    /// Applies finality up to `finalized` to every tracked contract.
+    ///
+    /// Returns the activated change, if any.
+    pub fn on_finalized(&mut self, finalized: BlockNumber) -> Option<PendingChange> {
+        self.trackers
+            .values_mut()
+            .filter_map(|tracker| tracker.on_finalized(finalized))
+            .last()
+    }
+
+    /// Returns the validator set that must have sealed `header`.
+    pub fn validators_for(&self, header: &GnosisHeader) -> Result<&[Address], ValidatorSetError> {
+        let (transition, source) = self
+            .config
+            .source_for_header(header)
+            .ok_or(ValidatorSetError::NoSource(header.number))?;
+        match source {
+            ValidatorSource::List(validators) => Ok(validators),
+            ValidatorSource::SafeContract(contract) | ValidatorSource::Contract(contract) => self
+                .trackers
+                .get(&transition)
+                .and_then(|tracker| tracker.validators_for(header))
+                .ok_or(ValidatorSetError::UntrackedContract {
+                    transition,
+                    contract: *contract,
+                }),
+        }
+    }
+
+    /// Checks the AuRa seal of `header` against its validator set, returning the signer.
+    pub fn verify_seal(&self, header: &GnosisHeader) -> Result<Address, ValidatorSetError> {
+        let validators = self.validators_for(header)?;
+        aura::verify_seal(header, validators).map_err(ValidatorSetError::Seal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_consensus::{Eip658Value, Receipt};
+    use alloy_primitives::{LogData, U256, address, hex};
+
+    const CONTRACT: Address = Address::with_last_byte(0xcc);
+
+    fn validators(bytes: &[u8]) -> Vec<Address> {
+        bytes.iter().map(|b| Address::with_last_byte(*b)).collect()
+    }
+
+    fn initiate_change_log(contract: Address, parent_hash: B256, set: Vec<Address>) -> Log {
+        let event = InitiateChange {
+            parentHash: parent_hash,
+            newSet: set,
+        };
+        Log {
+            address: contract,
+            data: LogData::new_unchecked(
+                vec![InitiateChange::SIGNATURE_HASH, parent_hash],
+                event.encode_data().into(),
+            ),
+        }
+    }
+
+    fn block(number: BlockNumber, logs: Vec<Log>) -> (GnosisHeader, Vec<Receipt<Log>>) {
+        let mut header = GnosisHeader {
+            number,
+            parent_hash: B256::with_last_byte(number as u8),
+            aura_step: Some(U256::from(number)),
+            aura_seal: Some(Default::default()),
+            ..Default::default()
+        };
+        for log in &logs {
+            header.logs_bloom.accrue_log(log);
+        }
+        let receipt = Receipt {
+            status: Eip658Value::Eip658(true),
+            cumulative_gas_used: 21_000,
+            logs,
+        };
+        (header, vec![receipt])
+    }
+
+    #[test]
+    fn test_finalize_change_selector() {
+        assert_eq!(finalize_change_input()[..], hex!("75286211"));
+        let call = FinalizeChangeSystemCall::new(CONTRACT);
+        assert_eq!(call.caller, SYSTEM_ADDRESS);
+        assert_eq!(call.to, CONTRACT);
+    }
+
+    #[test]
+    fn test_decode_initiate_change() {
+        let parent_hash = B256::with_last_byte(7);
+        let log = initiate_change_log(CONTRACT, parent_hash, validators(&[1, 2, 3]));
+        let (header, receipts) = block(7, vec![log]);
+        assert!(may_signal_change(&header, CONTRACT));
+        assert_eq!(
+            decode_initiate_change(&header, CONTRACT, &receipts),
+            Some(validators(&[1, 2, 3]))
+        );
+
+        // Other contracts are ignored
+        assert!(!may_signal_change(&header, Address::with_last_byte(0xdd)));
+        assert_eq!(
+            decode_initiate_change(&header, Address::with_last_byte(0xdd), &receipts),
+            None
+        );
+    }
+
+    #[test]
+    fn test_decode_ignores_wrong_parent_hash() {
+        let log = initiate_change_log(CONTRACT, B256::with_last_byte(0xff), validators(&[1]));
+        let (mut header, receipts) = block(7, vec![log.clone()]);
+        // Make the bloom match, while the log topic still doesn't
+        header.logs_bloom.accrue_raw_log(
+            CONTRACT,
+            &[InitiateChange::SIGNATURE_HASH, header.parent_hash],
+        );
+        assert_eq!(decode_initiate_change(&header, CONTRACT, &receipts), None);
+    }
+
+    #[test]
+    fn test_tracker_pending_and_finalized() {
+        let mut tracker = ValidatorSetTracker::new(CONTRACT, 10, validators(&[1, 2]));
+
+        let (header, receipts) = block(10, vec![]);
+        assert_eq!(tracker.on_block(&header, &receipts), Ok(None));
+
+        let log = initiate_change_log(CONTRACT, B256::with_last_byte(11), validators(&[3, 4, 5]));
+        let (header, receipts) = block(11, vec![log]);
+        let pending = tracker.on_block(&header, &receipts).unwrap().unwrap();
+        assert_eq!(pending.block_number, 11);
+        assert_eq!(pending.validators, validators(&[3, 4, 5]));
+
+        // Still sealed by the old set while pending
+        let (header, receipts) = block(12, vec![]);
+        tracker.on_block(&header, &receipts).unwrap();
+        assert_eq!(tracker.on_finalized(10), None);
+        assert_eq!(tracker.current(), validators(&[1, 2]).as_slice());
+
+        // Block 11 becomes final while processing block 12, so block 13 uses the new set
+        let change = tracker.on_finalized(11).unwrap();
+        assert_eq!(change.validators, validators(&[3, 4, 5]));
+        assert!(tracker.pending().is_none());
+        assert_eq!(
+            tracker.validators_at(12),
+            Some(validators(&[1, 2]).as_slice())
+        );
+        assert_eq!(
+            tracker.validators_at(13),
+            Some(validators(&[3, 4, 5]).as_slice())
+        );
+        assert_eq!(tracker.validators_at(9), None);
+
+        let (header, _) = block(13, vec![]);
+        assert_eq!(
+            tracker.validators_for(&header),
+            Some(validators(&[3, 4, 5]).as_slice())
+        );
+    }
+
+    #[test]
+    fn test_tracker_rejects_out_of_order() {
+        let mut tracker = ValidatorSetTracker::new(CONTRACT, 10, validators(&[1]));
+        let (header, receipts) = block(12, vec![]);
+        assert_eq!(
+            tracker.on_block(&header, &receipts),
+            Err(ValidatorSetError::OutOfOrder {
+                expected: 10,
+                got: 12
+            })
+        );
+    }
+
+    const GNOSIS_VALIDATORS_JSON: &str = r#"{
+        "multi": {
+            "0": { "list": ["0xcace5b3c29211740e595850e80478416ee77ca21"] },
+            "1300": { "safeContract": "0x22e1229a2c5b95a60983b5577f745a603284f535" },
+            "0x8c2c79": { "contract": "0xb87be9f7196f2ae084ca1de6af5264292976e013" }
+        }
+    }"#;
+
+    #[test]
+    fn test_parse_multi_config() {
+        let config: ValidatorSetConfig = serde_json::from_str(GNOSIS_VALIDATORS_JSON).unwrap();
+        let transitions: Vec<_> = config.transitions().map(|(block, _)| block).collect();
+        assert_eq!(transitions, vec![0, 1300, 9_186_425]);
+
+        assert!(matches!(
+            config.source_at(1),
+            Some((0, ValidatorSource::List(_)))
+        ));
+        assert!(matches!(
+            config.source_at(1299),
+            Some((0, ValidatorSource::List(_)))
+        ));
+        // A transition applies to its own block
+        assert_eq!(
+            config
+                .source_at(1300)
+                .and_then(|(_, source)| source.contract()),
+            Some(address!("22e1229a2c5b95a60983b5577f745a603284f535"))
+        );
+        assert!(matches!(
+            config.source_at(9_186_424),
+            Some((1300, ValidatorSource::SafeContract(_)))
+        ));
+        assert!(matches!(
+            config.source_at(9_186_425),
+            Some((9_186_425, ValidatorSource::Contract(_)))
+        ));
+        assert!(matches!(
+            config.source_at(30_000_000),
+            Some((9_186_425, ValidatorSource::Contract(_)))
+        ));
+
+        // Serialized back as a multi config with decimal keys
+        let json = serde_json::to_value(&config).unwrap();
+        assert!(json["multi"]["9186425"]["contract"].is_string());
+        assert_eq!(
+            serde_json::from_value::<ValidatorSetConfig>(json).unwrap(),
+            config
+        );
+    }
+
+    #[test]
+    fn test_parse_single_source() {
+        let config: ValidatorSetConfig =
+            serde_json::from_str(r#"{"list": ["0x00000000000000000000000000000000000000aa"]}"#)
+                .unwrap();
+        assert_eq!(
+            config.source_at(5),
+            Some((0, &ValidatorSource::List(validators(&[0xaa]))))
+        );
+
+        assert!(
+            serde_json::from_str::<ValidatorSetConfig>(r#"{"multi": {"x": {"list": []}}}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_multi_validator_set() {
+        let config = ValidatorSetConfig::new([
+            (0, ValidatorSource::List(validators(&[1]))),
+            (10, ValidatorSource::SafeContract(CONTRACT)),
+        ]);
+        let mut sets = MultiValidatorSet::new(config);
+
+        let (header, receipts) = block(9, vec![]);
+        assert_eq!(
+            sets.validators_for(&header),
+            Ok(validators(&[1]).as_slice())
+        );
+        assert_eq!(sets.on_block(&header, &receipts), Ok(None));
+
+        // The transition block is sealed by the contract
+        let (header, _) = block(10, vec![]);
+        assert_eq!(
+            sets.validators_for(&header),
+            Err(ValidatorSetError::UntrackedContract {
+                transition: 10,
+                contract: CONTRACT
+            })
+        );
+        assert_eq!(
+            sets.start_contract(0, vec![]).err(),
+            Some(ValidatorSetError::NotAContract(0))
+        );
+
+        sets.start_contract(10, validators(&[2, 3])).unwrap();
+        assert_eq!(
+            sets.validators_for(&header),
+            Ok(validators(&[2, 3]).as_slice())
+        );
+
+        let log = initiate_change_log(CONTRACT, header.parent_hash, validators(&[4]));
+        let (header, receipts) = block(10, vec![log]);
+        assert!(sets.on_block(&header, &receipts).unwrap().is_some());
+        assert!(sets.on_finalized(10).is_some());
+
+        let (header, _) = block(11, vec![]);
+        assert_eq!(
+            sets.validators_for(&header),
+            Ok(validators(&[4]).as_slice())
+        );
+        assert_eq!(
+            sets.tracker(10).map(|tracker| tracker.contract()),
+            Some(CONTRACT)
+        );
+
+        // The test headers carry an empty seal
+        assert_eq!(
+            sets.verify_seal(&header),
+            Err(ValidatorSetError::Seal(SealError::InvalidSignature))
+        );
+    }
+}
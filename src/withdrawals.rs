@@ -0,0 +1,291 @@
+use alloy_consensus::proofs::calculate_withdrawals_root;
+use alloy_eips::{eip4788::SYSTEM_ADDRESS, eip4895::Withdrawal};
+use alloy_primitives::{Address, B256, Bytes, U256, address};
+use alloy_sol_types::{SolCall, sol};
+
+use crate::header::GnosisHeader;
+
+/// Address of the deposit contract on Gnosis Chain mainnet.
+pub const GNOSIS_DEPOSIT_CONTRACT_ADDRESS: Address =
+    address!("0b98057ea310f4d31f2a452b414647007d1645d9");
+
+/// Address of the deposit contract on the Chiado testnet.
+pub const CHIADO_DEPOSIT_CONTRACT_ADDRESS: Address =
+    address!("b97036a26259b7147018913bd58a774cf91acf25");
+
+/// Number of previously failed withdrawals the deposit contract retries on every system call.
+pub const MAX_FAILED_WITHDRAWALS_TO_PROCESS: u64 = 4;
+
+/// Gas limit of the withdrawals system call, the same as every other system call.
+pub const SYSTEM_CALL_GAS_LIMIT: u64 = 30_000_000;
+
+/// Number of mGNO per GNO. Beacon chain balances on Gnosis are denominated in mGNO.
+pub const MGNO_PER_GNO: u64 = 32;
+
+sol! {
+    /// Pays out beacon chain withdrawals in GNO, called by the system at the end of each block.
+    function executeSystemWithdrawals(
+        uint256 maxFailedWithdrawalsToProcess,
+        uint64[] amounts,
+        address[] addresses
+    );
+}
+
+/// Returns the deposit contract address for a known Gnosis chain id.
+pub const fn deposit_contract_address(chain_id: u64) -> Option<Address> {
+    match chain_id {
+        100 => Some(GNOSIS_DEPOSIT_CONTRACT_ADDRESS),
+        10200 => Some(CHIADO_DEPOSIT_CONTRACT_ADDRESS),
+        _ => None,
+    }
+}
+
+/// Converts a withdrawal amount from beacon units (gwei of mGNO) to GNO token units (wei).
+///
+/// This mirrors the conversion done by the deposit contract: `amount * 1 gwei / 32`.
+pub fn withdrawal_amount_to_gno(amount: u64) -> U256 {
+    U256::from(amount) * U256::from(1_000_000_000u64) / U256::from(MGNO_PER_GNO)
+}
+
+/// Converts a GNO token amount (wei) to beacon units (gwei of mGNO).
+///
+/// Returns `None` if the result does not fit into a `u64`. Amounts that are not a multiple of
+/// the smallest representable beacon unit are rounded down.
+pub fn gno_to_withdrawal_amount(gno: U256) -> Option<u64> {
+    (gno.checked_mul(U256::from(MGNO_PER_GNO))? / U256::from(1_000_000_000u64))
+        .try_into()
+        .ok()
+}
+
+/// Errors returned when checking a header against its withdrawals.
+#[derive(Debug, Clone, PartialEq, Eq, derive_more::Display, derive_more::Error)]
+pub enum WithdrawalsError {
+    /// The header predates Shanghai and has no withdrawals root.
+    #[display("header has no withdrawals root")]
+    MissingWithdrawalsRoot,
+    /// The withdrawals root in the header does not match the withdrawals.
+    #[display("withdrawals root mismatch: header {header}, computed {computed}")]
+    RootMismatch {
+        /// Root stored in the header.
+        header: B256,
+        /// Root computed from the withdrawals.
+        computed: B256,
+    },
+}
+
+/// Computes the withdrawals root of a block.
+///
+/// The root commits to the withdrawals as issued by the beacon chain, in beacon units, exactly as
+/// on Ethereum. Only their execution differs on Gnosis.
+pub fn withdrawals_root(withdrawals: &[Withdrawal]) -> B256 {
+    calculate_withdrawals_root(withdrawals)
+}
+
+/// Checks that the header commits to the given withdrawals.
+pub fn verify_withdrawals_root(
+    header: &GnosisHeader,
+    withdrawals: &[Withdrawal],
+) -> Result<(), WithdrawalsError> {
+    let header_root = header
+        .withdrawals_root
+        .ok_or(WithdrawalsError::MissingWithdrawalsRoot)?;
+    let computed = withdrawals_root(withdrawals);
+    if header_root != computed {
+        return Err(WithdrawalsError::RootMismatch {
+            header: header_root,
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// The system call that executes a block's withdrawals on Gnosis.
+///
+/// Withdrawals are not credited as native balance. Instead the execution layer calls
+/// `executeSystemWithdrawals` on the deposit contract from [`SYSTEM_ADDRESS`] after all
+/// transactions, and the contract transfers GNO to the withdrawal addresses. The call is made for
+/// every post-Shanghai block, even if it has no withdrawals, so that failed withdrawals are
+/// retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalsSystemCall {
+    /// Sender of the call.
+    pub caller: Address,
+    /// The deposit contract.
+    pub to: Address,
+    /// Gas limit of the call.
+    pub gas_limit: u64,
+    /// ABI encoded `executeSystemWithdrawals` calldata.
+    pub input: Bytes,
+}
+
+impl WithdrawalsSystemCall {
+    /// Builds the system call executing `withdrawals` against the given deposit contract.
+    pub fn new(deposit_contract: Address, withdrawals: &[Withdrawal]) -> Self {
+        let call = executeSystemWithdrawalsCall {
+            maxFailedWithdrawalsToProcess: U256::from(MAX_FAILED_WITHDRAWALS_TO_PROCESS),
+            amounts: withdrawals.iter().map(|w| w.amount).collect(),
+            addresses: withdrawals.iter().map(|w| w.address).collect(),
+        };
+        Self {
+            caller: SYSTEM_ADDRESS,
+            to: deposit_contract,
+            gas_limit: SYSTEM_CALL_GAS_LIMIT,
+            input: call.abi_encode().into(),
+        }
+    }
+
+    /// Builds the system call for `header`, or `None` if the header predates Shanghai.
+    pub fn for_header(
+        header: &GnosisHeader,
+        deposit_contract: Address,
+        withdrawals: &[Withdrawal],
+    ) -> Option<Self> {
+        header
+            .shanghai_active()
+            .then(|| Self::new(deposit_contract, withdrawals))
+    }
+
+    /// Decodes the calldata of an `executeSystemWithdrawals` call.
+    pub fn decode_input(input: &[u8]) -> alloy_sol_types::Result<executeSystemWithdrawalsCall> {
+        executeSystemWithdrawalsCall::abi_decode(input)
+    }
+
+    /// Returns the GNO amount each address receives, in call order.
+    ///
+    /// Returns `None` if the calldata cannot be decoded.
+    pub fn gno_payouts(&self) -> Option<Vec<(Address, U256)>> {
+        let call = Self::decode_input(&self.input).ok()?;
+        Some(
+            call.addresses
+                .into_iter()
+                .zip(call.amounts)
+                .map(|(address, amount)| (address, withdrawal_amount_to_gno(amount)))
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, hex};
+
+    fn sample_withdrawals() -> Vec<Withdrawal> {
+        vec![
+            Withdrawal {
+                index: 0,
+                validator_index: 1,
+                address: Address::with_last_byte(0x11),
+                amount: 32_000_000_000,
+            },
+            Withdrawal {
+                index: 1,
+                validator_index: 2,
+                address: Address::with_last_byte(0x22),
+                amount: 5,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_amount_conversion() {
+        // 32 mGNO is one GNO
+        assert_eq!(
+            withdrawal_amount_to_gno(32_000_000_000),
+            U256::from(1_000_000_000_000_000_000u64)
+        );
+        assert_eq!(
+            gno_to_withdrawal_amount(U256::from(1_000_000_000_000_000_000u64)),
+            Some(32_000_000_000)
+        );
+        assert_eq!(gno_to_withdrawal_amount(U256::MAX), None);
+
+        // Small amounts round down like the contract does
+        assert_eq!(withdrawal_amount_to_gno(1), U256::from(31_250_000u64));
+    }
+
+    #[test]
+    fn test_system_call_calldata() {
+        let call =
+            WithdrawalsSystemCall::new(GNOSIS_DEPOSIT_CONTRACT_ADDRESS, &sample_withdrawals());
+        assert_eq!(call.caller, SYSTEM_ADDRESS);
+        assert_eq!(call.to, GNOSIS_DEPOSIT_CONTRACT_ADDRESS);
+        assert_eq!(
+            call.input[..4],
+            hex!("79d0c0bc"),
+            "selector of executeSystemWithdrawals(uint256,uint64[],address[])"
+        );
+
+        let decoded = WithdrawalsSystemCall::decode_input(&call.input).unwrap();
+        assert_eq!(decoded.maxFailedWithdrawalsToProcess, U256::from(4));
+        assert_eq!(decoded.amounts, vec![32_000_000_000, 5]);
+        assert_eq!(
+            decoded.addresses,
+            vec![Address::with_last_byte(0x11), Address::with_last_byte(0x22)]
+        );
+
+        let payouts = call.gno_payouts().unwrap();
+        assert_eq!(payouts[0].1, U256::from(1_000_000_000_000_000_000u64));
+    }
+
+    #[test]
+    fn test_system_call_without_withdrawals() {
+        let call = WithdrawalsSystemCall::new(CHIADO_DEPOSIT_CONTRACT_ADDRESS, &[]);
+        let decoded = WithdrawalsSystemCall::decode_input(&call.input).unwrap();
+        assert!(decoded.amounts.is_empty());
+        assert!(decoded.addresses.is_empty());
+    }
+
+    #[test]
+    fn test_system_call_for_header() {
+        let mut header = GnosisHeader::default();
+        assert!(
+            WithdrawalsSystemCall::for_header(&header, GNOSIS_DEPOSIT_CONTRACT_ADDRESS, &[])
+                .is_none()
+        );
+
+        header.withdrawals_root = Some(withdrawals_root(&[]));
+        assert!(
+            WithdrawalsSystemCall::for_header(&header, GNOSIS_DEPOSIT_CONTRACT_ADDRESS, &[])
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_verify_withdrawals_root() {
+        let withdrawals = sample_withdrawals();
+        let mut header = GnosisHeader::default();
+        assert_eq!(
+            verify_withdrawals_root(&header, &withdrawals),
+            Err(WithdrawalsError::MissingWithdrawalsRoot)
+        );
+
+        header.withdrawals_root = Some(withdrawals_root(&withdrawals));
+        assert_eq!(verify_withdrawals_root(&header, &withdrawals), Ok(()));
+        assert!(matches!(
+            verify_withdrawals_root(&header, &withdrawals[..1]),
+            Err(WithdrawalsError::RootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_empty_withdrawals_root() {
+        assert_eq!(
+            withdrawals_root(&[]),
+            b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+        );
+    }
+
+    #[test]
+    fn test_deposit_contract_address() {
+        assert_eq!(
+            deposit_contract_address(100),
+            Some(GNOSIS_DEPOSIT_CONTRACT_ADDRESS)
+        );
+        assert_eq!(
+            deposit_contract_address(10200),
+            Some(CHIADO_DEPOSIT_CONTRACT_ADDRESS)
+        );
+        assert_eq!(deposit_contract_address(1), None);
+    }
+}